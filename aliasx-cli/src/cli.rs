@@ -1,16 +1,24 @@
 use aliasx_core::{
-    aliases, config_generator::ConfigGenerator, history::History, task_collection::TaskCollection, task_filter::TaskFilter, task_reader::TaskFormat, tasks::{self}
+    aliases::{NativeOptions, ShellKind},
+    config_editor::ConfigEditor, config_generator::ConfigGenerator, history::History, input::{Input, REDACTED}, shell_export::ShellExporter, task_collection::{IndexedTask, TaskCollection}, task_filter::TaskFilter, task_reader::TaskFormat, tasks
+};
+use aliasx_tui::{
+    fuzzy_finder, fuzzy_input, fuzzy_multi_input, fuzzy_select, task_fuzzy_finder, text_input,
+    FuzzyAction, FuzzyConfig, FuzzyInput, TextInputConfig, TuiSession, ValueCheck,
 };
-use aliasx_tui::{fuzzy_finder, task_fuzzy_finder, FuzzyConfig, TuiSession};
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use indexmap::IndexMap;
 use std::{ops::Index, path::PathBuf};
 
+use crate::wizard;
+
 #[derive(ValueEnum, Clone, Debug, Copy)]
 enum TaskFilterCli {
     All,
     Local,
     Global,
+    Native,
+    Git,
 }
 
 impl From<TaskFilterCli> for TaskFilter {
@@ -19,6 +27,8 @@ impl From<TaskFilterCli> for TaskFilter {
             TaskFilterCli::All => TaskFilter::All,
             TaskFilterCli::Local => TaskFilter::Local,
             TaskFilterCli::Global => TaskFilter::Global,
+            TaskFilterCli::Native => TaskFilter::Native,
+            TaskFilterCli::Git => TaskFilter::Git,
         }
     }
 }
@@ -38,6 +48,23 @@ enum TaskFormatCli {
     Json,
 }
 
+#[derive(ValueEnum, Clone, Debug, Copy)]
+enum ShellCli {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl From<ShellCli> for ShellKind {
+    fn from(s: ShellCli) -> Self {
+        match s {
+            ShellCli::Bash => ShellKind::Bash,
+            ShellCli::Zsh => ShellKind::Zsh,
+            ShellCli::Fish => ShellKind::Fish,
+        }
+    }
+}
+
 #[derive(Args)]
 struct TaskOptions {
     /// the index of alias to handle
@@ -52,15 +79,57 @@ struct TaskOptions {
     #[arg(value_enum, short, long, default_value_t = TaskFilterCli::All)]
     filter: TaskFilterCli,
 
-    /// only apply to native aliases
+    /// only apply to native aliases (same as --filter native)
     #[arg(short, long)]
     native: bool,
 
+    /// include native aliases together with the configured tasks
+    #[arg(long)]
+    include_native: bool,
+
+    /// include git aliases together with the configured tasks
+    #[arg(long)]
+    include_git: bool,
+
+    /// include shell functions in native aliases (implies --include-native)
+    #[arg(long)]
+    functions: bool,
+
+    /// reload native aliases instead of using the cache (implies --include-native)
+    #[arg(long)]
+    refresh: bool,
+
     /// enable conditions
     #[arg(short, long)]
     conditions: Option<bool>,
 }
 
+impl TaskOptions {
+    fn task_filter(&self) -> TaskFilter {
+        if self.native {
+            TaskFilter::Native
+        } else {
+            self.filter.into()
+        }
+    }
+
+    fn include_git(&self) -> bool {
+        self.include_git || self.task_filter() == TaskFilter::Git
+    }
+
+    fn native_options(&self) -> Option<NativeOptions> {
+        let include = self.include_native
+            || self.functions
+            || self.refresh
+            || self.task_filter() == TaskFilter::Native;
+
+        include.then_some(NativeOptions {
+            functions: self.functions,
+            refresh: self.refresh,
+        })
+    }
+}
+
 #[derive(Parser)]
 #[command(version, about = "Alias e(x)tended CLI")]
 struct Cli {
@@ -79,6 +148,18 @@ enum Commands {
         /// id of task to run
         #[arg()]
         id: Option<String>,
+
+        /// value for an input, eg. --input env=prod (can be repeated)
+        #[arg(long = "input", value_name = "ID=VALUE", value_parser = parse_input_value)]
+        inputs: Vec<(String, String)>,
+
+        /// never prompt - inputs without a value use their default or fail
+        #[arg(long)]
+        no_interactive: bool,
+
+        /// extra arguments appended to the command (after --)
+        #[arg(last = true)]
+        args: Vec<String>,
     },
 
     /// list all aliases (list)
@@ -102,6 +183,10 @@ enum Commands {
     /// run validation on configs files
     #[command()]
     Validate {
+        /// run the options_command of inputs to check they produce options
+        #[arg(long)]
+        run_commands: bool,
+
         #[command(flatten)]
         task_options: TaskOptions,
     },
@@ -115,6 +200,43 @@ enum Commands {
 
         #[command(flatten)]
         task_options: TaskOptions,
+
+        #[command(subcommand)]
+        action: Option<HistoryCommands>,
+    },
+
+    /// open the config defining a task in $EDITOR
+    Edit {
+        #[command(flatten)]
+        task_options: TaskOptions,
+
+        /// id of task to edit
+        #[arg()]
+        id: Option<String>,
+    },
+
+    /// add a new task to a local or global config
+    Add,
+
+    /// remove a task from its config (rm)
+    #[command(aliases = ["rm"])]
+    Remove {
+        #[command(flatten)]
+        task_options: TaskOptions,
+
+        /// id of task to remove
+        #[arg()]
+        id: Option<String>,
+    },
+
+    /// export tasks as shell functions
+    Export {
+        /// shell to generate functions for (defaults to $SHELL)
+        #[arg(value_enum, short, long)]
+        shell: Option<ShellCli>,
+
+        #[command(flatten)]
+        task_options: TaskOptions,
     },
 
     /// create or convert existing configs
@@ -124,6 +246,28 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum HistoryCommands {
+    /// turn a history entry into a new task
+    Promote {
+        /// index of the history entry, opens the history tui if omitted
+        #[arg(short, long)]
+        index: Option<usize>,
+    },
+}
+
+const EDIT_ACTION: FuzzyAction = FuzzyAction {
+    key: 'e',
+    hint: "^e",
+    name: "edit",
+};
+
+const PROMOTE_ACTION: FuzzyAction = FuzzyAction {
+    key: 'p',
+    hint: "^p",
+    name: "promote",
+};
+
 #[derive(Subcommand)]
 enum ConfigGeneratorSubCommands {
     /// print a minimal example config
@@ -145,17 +289,33 @@ enum ConfigGeneratorSubCommands {
         #[arg()]
         path: String,
     },
+
+    /// print a JSON Schema for config files
+    Schema,
+
+    /// convert existing config to a vscode tasks.json
+    ToVscode {
+        /// path to yaml or json config
+        #[arg()]
+        path: String,
+    },
+}
+
+fn parse_input_value(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(id, value)| (id.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected <id>=<value>, got '{}'", s))
 }
 
 fn get_tasks(task_options: &TaskOptions) -> anyhow::Result<TaskCollection> {
     let enable_conditions = task_options.conditions.unwrap_or(true);
-    let tasks = if task_options.native {
-        aliases::get_aliases_as_tasks()?
-    } else {
-        tasks::get_all_tasks(task_options.filter.into(), enable_conditions)?
-    };
 
-    Ok(tasks)
+    tasks::get_all_tasks(
+        task_options.task_filter(),
+        enable_conditions,
+        task_options.native_options(),
+        task_options.include_git(),
+    )
 }
 
 pub fn parse_and_run() -> anyhow::Result<()> {
@@ -166,7 +326,7 @@ pub fn parse_and_run() -> anyhow::Result<()> {
 fn run(cli: &Cli) -> anyhow::Result<()> {
     match &cli.command {
         Some(Commands::Ls { task_options }) => {
-            let tasks = get_tasks(&task_options)?;
+            let tasks = get_tasks(task_options)?;
             if let Some(idx) = task_options.index {
                 tasks.list_at(idx, task_options.verbose)?;
             } else {
@@ -178,45 +338,61 @@ fn run(cli: &Cli) -> anyhow::Result<()> {
             task_options,
             query,
         }) => {
-            let tasks = get_tasks(&task_options)?;
+            let tasks = get_tasks(task_options)?;
             run_fzf_task(&tasks, query.as_deref().unwrap_or(""), task_options.verbose)?;
         }
 
-        Some(Commands::Validate { task_options }) => {
-            let tasks = tasks::get_all_tasks(task_options.filter.into(), false)?; // always disable conditions
+        Some(Commands::Validate {
+            run_commands,
+            task_options,
+        }) => {
+            let tasks = tasks::get_all_tasks(
+                task_options.task_filter(),
+                false, // always disable conditions
+                None,
+                task_options.include_git(),
+            )?;
             if let Some(idx) = task_options.index {
-                tasks.validate_at(idx, task_options.verbose)?;
+                tasks.validate_at(idx, task_options.verbose, *run_commands)?;
             } else {
-                tasks.validate_all(task_options.verbose);
+                tasks.validate_all(task_options.verbose, *run_commands);
             }
         }
 
         Some(Commands::History {
             clear,
             task_options,
+            action,
         }) => {
             if *clear {
                 History::clear()?;
                 return Ok(());
             }
 
-            let history = History::load_filtered(task_options.filter.into())?;
+            let history = History::load_filtered(task_options.task_filter())?;
 
-            let idx = if let Some(idx) = task_options.index {
-                idx
-            } else {
-                let mut session = TuiSession::new()?;
-                let selected_idx = fuzzy_finder(
-                    &history,
-                    "History",
-                    FuzzyConfig {
-                        show_details: true,
-                        ..Default::default()
-                    },
-                    &mut session,
-                )?;
-                drop(session);
-                selected_idx
+            let (idx, promote) = match (action, task_options.index) {
+                (Some(HistoryCommands::Promote { index: Some(idx) }), _) => (*idx, true),
+                (Some(HistoryCommands::Promote { index: None }), Some(idx)) => (idx, true),
+                (None, Some(idx)) => (idx, false),
+                _ => {
+                    let mut session = TuiSession::new()?;
+                    let selection = fuzzy_select(
+                        &history,
+                        "History",
+                        FuzzyConfig {
+                            show_details: true,
+                            actions: vec![PROMOTE_ACTION],
+                            ..Default::default()
+                        },
+                        &mut session,
+                    )?;
+                    drop(session);
+                    (
+                        selection.idx,
+                        action.is_some() || selection.action == Some(PROMOTE_ACTION),
+                    )
+                }
             };
 
             if idx >= history.len() {
@@ -227,32 +403,103 @@ fn run(cli: &Cli) -> anyhow::Result<()> {
                 ));
             }
 
+            if promote {
+                return wizard::run_promote_wizard(history.index(idx));
+            }
+
             let selected = history.index(idx);
+
+            // secrets aren't stored - prompt for them again through the task
+            if selected.is_redacted() {
+                let tasks = tasks::get_all_tasks(TaskFilter::All, false, None, false)?;
+                let itask = tasks
+                    .indexed_tasks()
+                    .into_iter()
+                    .find(|itask| {
+                        itask.task.label == selected.task_name
+                            && itask.source.scope == selected.scope
+                    })
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "task '{}' no longer exists - it used secret inputs so it can't be re-run from the history",
+                            selected.task_name
+                        )
+                    })?;
+
+                let selections = selected
+                    .input_selections
+                    .iter()
+                    .filter(|(_, value)| value.as_str() != REDACTED)
+                    .map(|(id, value)| (id.clone(), value.clone()))
+                    .collect();
+
+                return run_task(&tasks, itask, selections, false, &[], task_options.verbose);
+            }
+
             let name = if task_options.verbose {
                 &selected.task_command
             } else {
                 &selected.task_name
             };
-            TaskCollection::run_command(name, &selected.task_command)?
+            TaskCollection::run_command(name, &selected.task_command, &[])?
         }
 
-        Some(Commands::Run { id, task_options }) => {
-            let tasks = get_tasks(&task_options)?;
-            let itask = match (task_options.index, &id) {
-                (Some(idx), None) => tasks.find_itask_from_idx(idx)?,
-                (None, Some(id)) => tasks.find_itask_from_id(id)?,
-                _ => {
-                    return Err(anyhow::anyhow!(
-                        "provide either an [ID] or --index, but not both"
-                    ))
-                }
-            };
+        Some(Commands::Run {
+            id,
+            task_options,
+            inputs,
+            no_interactive,
+            args,
+        }) => {
+            let tasks = get_tasks(task_options)?;
+            let itask = find_itask(&tasks, task_options.index, id.as_deref())?;
+
+            let input_selections = itask
+                .source
+                .preset_selections(&itask.task.command, inputs)?;
+
+            run_task(
+                &tasks,
+                itask,
+                input_selections,
+                *no_interactive,
+                args,
+                task_options.verbose,
+            )?;
+        }
 
-            let mut session = TuiSession::new()?;
-            let input_selections = run_fzf_inputs(&tasks, itask.idx, &mut session)?;
-            drop(session);
+        Some(Commands::Edit { id, task_options }) => {
+            let tasks = get_tasks(task_options)?;
+            let itask = find_itask(&tasks, task_options.index, id.as_deref())?;
+            TaskCollection::edit(&itask)?;
+        }
 
-            tasks.execute(&itask, &input_selections, task_options.verbose)?;
+        Some(Commands::Add) => wizard::run_add_wizard()?,
+
+        Some(Commands::Remove { id, task_options }) => {
+            let tasks = tasks::get_all_tasks(task_options.task_filter(), false, None, false)?;
+            let itask = find_itask(&tasks, task_options.index, id.as_deref())?;
+            let path = itask.source.path.as_ref().ok_or_else(|| {
+                anyhow::anyhow!("task '{}' is not defined in a config file", itask.task.label)
+            })?;
+
+            ConfigEditor::remove_task(path, itask.task)?;
+            println!("Removed '{}' from {}", itask.task.label, path.display());
+        }
+
+        Some(Commands::Export {
+            shell,
+            task_options,
+        }) => {
+            let tasks = get_tasks(task_options)?;
+            let shell = shell.map_or_else(
+                || {
+                    let path = std::env::var("SHELL").unwrap_or_default();
+                    ShellKind::from_path(&path)
+                },
+                ShellKind::from,
+            );
+            ShellExporter::print(&tasks, shell);
         }
 
         Some(Commands::ConfigGenerator { command }) => match command {
@@ -265,10 +512,14 @@ fn run(cli: &Cli) -> anyhow::Result<()> {
             ConfigGeneratorSubCommands::YamlToJson { path } => {
                 ConfigGenerator::convert_yaml_to_json(PathBuf::from(path))?
             }
+            ConfigGeneratorSubCommands::Schema => ConfigGenerator::print_schema()?,
+            ConfigGeneratorSubCommands::ToVscode { path } => {
+                ConfigGenerator::convert_to_vscode(PathBuf::from(path))?
+            }
         },
 
         None => {
-            let tasks = tasks::get_all_tasks(TaskFilter::All, true)?;
+            let tasks = tasks::get_all_tasks(TaskFilter::All, true, None, false)?;
             run_fzf_task(&tasks, "", false)?;
             return Ok(());
         }
@@ -277,43 +528,224 @@ fn run(cli: &Cli) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Prompts for the inputs missing in `input_selections` and runs the task.
+fn run_task(
+    tasks: &TaskCollection,
+    itask: IndexedTask,
+    mut input_selections: IndexMap<String, String>,
+    no_interactive: bool,
+    args: &[String],
+    verbose: bool,
+) -> anyhow::Result<()> {
+    let inputs = tasks.required_inputs_for_task(itask.idx)?;
+    let missing = inputs
+        .iter()
+        .any(|input| !input_selections.contains_key(&input.id));
+
+    if no_interactive {
+        for input in inputs {
+            if let Some(value) = input_selections.get(&input.id) {
+                check_dependent_preset(&itask, input, value, &input_selections)?;
+                continue;
+            }
+
+            let value = input.resolve_default()?.0.default.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "no value for input '{}' - use --input {}=<value> or {}",
+                    input.id,
+                    input.id,
+                    input.env_var()
+                )
+            })?;
+            itask
+                .source
+                .check_selection(input, &value, &input_selections)
+                .map_err(|err| anyhow::anyhow!("default of input '{}': {:#}", input.id, err))?;
+            input_selections.insert(input.id.clone(), value);
+        }
+    } else if missing {
+        let mut session = TuiSession::new()?;
+        input_selections = run_fzf_inputs(tasks, itask.idx, input_selections, &mut session)?;
+        drop(session);
+    }
+
+    tasks.execute(&itask, &input_selections, args, verbose)
+}
+
+fn find_itask<'a>(
+    tasks: &'a TaskCollection,
+    index: Option<usize>,
+    id: Option<&str>,
+) -> anyhow::Result<IndexedTask<'a>> {
+    match (index, id) {
+        (Some(idx), None) => tasks.find_itask_from_idx(idx),
+        (None, Some(id)) => tasks.find_itask_from_id(id),
+        _ => Err(anyhow::anyhow!(
+            "provide either an [ID] or --index, but not both"
+        )),
+    }
+}
+
 fn run_fzf_task(tasks: &TaskCollection, query: &str, verbose: bool) -> anyhow::Result<()> {
     let mut session = TuiSession::new()?;
 
     let entries = tasks.indexed_tasks();
-    let selected_idx = task_fuzzy_finder(&entries, &tasks, &mut session, query, verbose)?;
-    let input_selections = run_fzf_inputs(tasks, selected_idx, &mut session)?;
+    let selection = task_fuzzy_finder(
+        &entries,
+        tasks,
+        &mut session,
+        query,
+        verbose,
+        vec![EDIT_ACTION],
+    )?;
+
+    let itask = tasks.find_itask_from_idx(selection.idx)?;
+    if selection.action == Some(EDIT_ACTION) {
+        drop(session);
+        return TaskCollection::edit(&itask);
+    }
+
+    let input_selections = run_fzf_inputs(tasks, selection.idx, IndexMap::new(), &mut session)?;
 
     drop(session);
 
-    let itask = tasks.find_itask_from_idx(selected_idx)?;
-    tasks.execute(&itask, &input_selections, verbose)
+    tasks.execute(&itask, &input_selections, &[], verbose)
+}
+
+/// Presets of `options_by` inputs are checked against all options when their parent isn't
+/// preset - once the parent is selected they have to be among its options.
+fn check_dependent_preset(
+    itask: &IndexedTask,
+    input: &Input,
+    value: &str,
+    selections: &IndexMap<String, String>,
+) -> anyhow::Result<()> {
+    if input.options_by.is_none() {
+        return Ok(());
+    }
+
+    itask.source.check_selection(input, value, selections)
+}
+
+/// Typed values have to satisfy the rules of the input (`pattern`, `min`/`max`, `required`).
+fn rules_check(input: &Input) -> ValueCheck {
+    let input = input.clone();
+    Box::new(move |value| input.check_rules(value))
 }
 
+/// Elements added to a `multiple` selection also must not contain the separator.
+fn custom_check(input: &Input) -> ValueCheck {
+    let input = input.clone();
+    Box::new(move |value| input.check_custom(value))
+}
+
+/// Prompts for the inputs of task `idx` that aren't already in `selections`.
 fn run_fzf_inputs(
     tasks: &TaskCollection,
     idx: usize,
+    mut selections: IndexMap<String, String>,
     session: &mut TuiSession,
 ) -> anyhow::Result<IndexMap<String, String>> {
     let inputs = tasks.required_inputs_for_task(idx)?;
-    let mut selections = IndexMap::new();
+    let itask = tasks.find_itask_from_idx(idx)?;
+    let config_dir = itask.source.config_dir();
+    let last_selections = History::last_selections(itask.task.selection_key()).unwrap_or_default();
 
     for input in inputs {
+        if let Some(value) = selections.get(&input.id) {
+            check_dependent_preset(&itask, input, value, &selections)?;
+            continue;
+        }
+
         let prompt = format!(
             "input | {}:",
             input.description.as_deref().unwrap_or(&input.id)
         );
-        let sel = fuzzy_finder(
-            &input.options,
-            &prompt,
-            FuzzyConfig {
-                has_details: false,
-                initial_position: input.get_default_selection(),
-                ..Default::default()
-            },
-            session,
-        )?;
-        selections.insert(input.id.clone(), input.options[sel].clone());
+
+        // failing to resolve the options falls back to typing the value
+        let (input, message) = match input
+            .select_options_by(&selections)
+            .and_then(|input| input.resolve_options(&config_dir))
+        {
+            Ok(resolved) => (resolved, None),
+            Err(err) => {
+                let mut fallback = input.clone();
+                fallback.options_command = None;
+                fallback.options_glob = None;
+                fallback.options_by = None;
+                fallback.allow_custom = true;
+                (fallback, Some(format!("{:#}", err)))
+            }
+        };
+
+        // dynamic defaults fall back to the static default
+        let (mut input, message, dynamic_default) = match input.resolve_default() {
+            Ok((resolved, dynamic_default)) => (resolved, message, dynamic_default),
+            Err(err) => {
+                let mut fallback = input.clone();
+                fallback.default_env = None;
+                fallback.default_command = None;
+                let message = message.or_else(|| Some(format!("{:#}", err)));
+                (fallback, message, false)
+            }
+        };
+
+        // start at the last selection unless the input prefers its default - a resolved
+        // dynamic default reflects the current state, so it wins as well
+        if let Some(last) = last_selections.get(&input.id) {
+            if !input.prefer_default && !dynamic_default && input.check_value(last).is_ok() {
+                input.default = Some(last.clone());
+            }
+        }
+
+        // descriptions are shown next to the options
+        let has_details = input.options.iter().any(|o| o.description.is_some());
+        let config = FuzzyConfig {
+            has_details,
+            show_details: has_details,
+            initial_position: input.get_default_selection(),
+            message: message.clone(),
+            check: Some(rules_check(&input)),
+            mask: input.is_secret(),
+            ..Default::default()
+        };
+
+        let value = if input.is_free_text() {
+            text_input(
+                &prompt,
+                TextInputConfig {
+                    hint: message,
+                    initial_value: input.default.clone().unwrap_or_default(),
+                    check: Some(rules_check(&input)),
+                    mask: input.is_secret(),
+                },
+                session,
+            )?
+        } else if input.multiple {
+            let config = FuzzyConfig {
+                initial_toggled: input.get_default_selections(),
+                check: Some(custom_check(&input)),
+                ..config
+            };
+            let values: Vec<String> =
+                fuzzy_multi_input(&input.options, &prompt, config, input.allow_custom, session)?
+                    .into_iter()
+                    .map(|sel| match sel {
+                        FuzzyInput::Item(sel) => input.options[sel].value.clone(),
+                        FuzzyInput::Custom(value) => value,
+                    })
+                    .collect();
+            input.join_values(&values)
+        } else if input.allow_custom {
+            match fuzzy_input(&input.options, &prompt, config, session)? {
+                FuzzyInput::Item(sel) => input.options[sel].value.clone(),
+                FuzzyInput::Custom(value) => value,
+            }
+        } else {
+            let sel = fuzzy_finder(&input.options, &prompt, config, session)?;
+            input.options[sel].value.clone()
+        };
+        selections.insert(input.id.clone(), value);
     }
 
     Ok(selections)
@@ -328,7 +760,7 @@ mod tests {
     #[test]
     fn test_no_subcommand() {
         let args = ["aliasx", "-i", "7", "-v"];
-        let cli = Cli::try_parse_from(&args);
+        let cli = Cli::try_parse_from(args);
 
         assert!(cli.is_err());
     }
@@ -336,7 +768,7 @@ mod tests {
     #[test]
     fn test_list_id() {
         let args = ["aliasx", "ls", "--index", "2"];
-        let cli = Cli::try_parse_from(&args).unwrap();
+        let cli = Cli::try_parse_from(args).unwrap();
 
         match cli.command {
             Some(Commands::Ls { task_options }) => {
@@ -349,7 +781,7 @@ mod tests {
     #[test]
     fn test_list_alias_ls() {
         let args = ["aliasx", "ls"];
-        let cli = Cli::try_parse_from(&args).unwrap();
+        let cli = Cli::try_parse_from(args).unwrap();
 
         match cli.command {
             Some(Commands::Ls { task_options }) => {
@@ -363,7 +795,7 @@ mod tests {
     #[test]
     fn test_fzf_with_query() {
         let args = ["aliasx", "f", "--query", "hello"];
-        let cli = Cli::try_parse_from(&args).unwrap();
+        let cli = Cli::try_parse_from(args).unwrap();
 
         match cli.command {
             Some(Commands::Fzf {
@@ -380,7 +812,7 @@ mod tests {
     #[test]
     fn test_fzf_with_native_aliases() {
         let args = ["aliasx", "fzf", "-n", "--query", "native"];
-        let cli = Cli::try_parse_from(&args).unwrap();
+        let cli = Cli::try_parse_from(args).unwrap();
 
         match cli.command {
             Some(Commands::Fzf {
@@ -388,19 +820,119 @@ mod tests {
                 task_options,
             }) => {
                 assert_eq!(query.unwrap(), "native");
-                assert_eq!(task_options.native, true);
+                assert!(task_options.native);
+            }
+            _ => panic!("wrong subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_native_functions() {
+        let args = ["aliasx", "ls", "-n", "--functions"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::Ls { task_options }) => {
+                assert!(task_options.native);
+                assert!(task_options.functions);
+            }
+            _ => panic!("wrong subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_native_filter() {
+        let args = ["aliasx", "ls", "-n"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::Ls { task_options }) => {
+                assert_eq!(task_options.task_filter(), TaskFilter::Native);
+                assert!(task_options.native_options().is_some());
+            }
+            _ => panic!("wrong subcommand"),
+        }
+
+        let args = ["aliasx", "ls", "--filter", "native"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::Ls { task_options }) => {
+                assert_eq!(task_options.task_filter(), TaskFilter::Native);
+                assert!(task_options.native_options().is_some());
+            }
+            _ => panic!("wrong subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_include_native() {
+        let args = ["aliasx", "ls"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::Ls { task_options }) => {
+                assert_eq!(task_options.task_filter(), TaskFilter::All);
+                assert!(task_options.native_options().is_none());
+            }
+            _ => panic!("wrong subcommand"),
+        }
+
+        let args = ["aliasx", "ls", "--include-native", "--refresh"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::Ls { task_options }) => {
+                assert_eq!(task_options.task_filter(), TaskFilter::All);
+                assert!(task_options.native_options().unwrap().refresh);
+            }
+            _ => panic!("wrong subcommand"),
+        }
+
+        // --functions and --refresh only make sense with native aliases
+        let args = ["aliasx", "ls", "--functions"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::Ls { task_options }) => {
+                assert!(task_options.native_options().unwrap().functions);
             }
             _ => panic!("wrong subcommand"),
         }
     }
 
+    #[test]
+    fn test_include_git() {
+        let args = ["aliasx", "ls"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::Ls { task_options }) => assert!(!task_options.include_git()),
+            _ => panic!("wrong subcommand"),
+        }
+
+        for args in [
+            ["aliasx", "ls", "--include-git"],
+            ["aliasx", "ls", "--filter=git"],
+        ] {
+            let cli = Cli::try_parse_from(args).unwrap();
+
+            match cli.command {
+                Some(Commands::Ls { task_options }) => assert!(task_options.include_git()),
+                _ => panic!("wrong subcommand"),
+            }
+        }
+    }
+
     #[test]
     fn test_run_command_with_id() {
         let args = ["aliasx", "run", "build"];
-        let cli = Cli::try_parse_from(&args).unwrap();
+        let cli = Cli::try_parse_from(args).unwrap();
 
         match cli.command {
-            Some(Commands::Run { id, task_options }) => {
+            Some(Commands::Run {
+                id, task_options, ..
+            }) => {
                 assert_eq!(id.unwrap(), "build");
                 assert!(task_options.index.is_none());
             }
@@ -411,10 +943,12 @@ mod tests {
     #[test]
     fn test_run_command_with_idx() {
         let args = ["aliasx", "run", "--index", "1"];
-        let cli = Cli::try_parse_from(&args).unwrap();
+        let cli = Cli::try_parse_from(args).unwrap();
 
         match cli.command {
-            Some(Commands::Run { id, task_options }) => {
+            Some(Commands::Run {
+                id, task_options, ..
+            }) => {
                 assert!(id.is_none());
                 assert_eq!(task_options.index.unwrap(), 1);
             }
@@ -422,13 +956,84 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_run_command_with_args() {
+        let args = ["aliasx", "run", "build", "--", "-v", "some arg"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::Run {
+                id,
+                task_options,
+                args,
+                ..
+            }) => {
+                assert_eq!(id.unwrap(), "build");
+                assert!(!task_options.verbose);
+                assert_eq!(args, vec!["-v", "some arg"]);
+            }
+            _ => panic!("wrong subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_run_command_with_inputs() {
+        let args = [
+            "aliasx",
+            "run",
+            "deploy",
+            "--input",
+            "env=prod",
+            "--input",
+            "query=a=b",
+            "--no-interactive",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::Run {
+                inputs,
+                no_interactive,
+                ..
+            }) => {
+                assert_eq!(
+                    inputs,
+                    vec![
+                        ("env".to_string(), "prod".to_string()),
+                        ("query".to_string(), "a=b".to_string())
+                    ]
+                );
+                assert!(no_interactive);
+            }
+            _ => panic!("wrong subcommand"),
+        }
+
+        let args = ["aliasx", "run", "deploy", "--input", "env"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_export_shell() {
+        let args = ["aliasx", "export", "--shell", "fish"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::Export { shell, .. }) => {
+                assert_eq!(ShellKind::from(shell.unwrap()), ShellKind::Fish);
+            }
+            _ => panic!("wrong subcommand"),
+        }
+    }
+
     #[test]
     fn test_run_command_with_no_id_nor_idx() {
         let args = ["aliasx", "run"];
-        let cli = Cli::try_parse_from(&args).unwrap();
+        let cli = Cli::try_parse_from(args).unwrap();
 
         match &cli.command {
-            Some(Commands::Run { id, task_options }) => {
+            Some(Commands::Run {
+                id, task_options, ..
+            }) => {
                 assert!(id.is_none());
                 assert!(task_options.index.is_none());
             }
@@ -442,10 +1047,12 @@ mod tests {
     #[test]
     fn test_run_command_with_both_id_nor_idx() {
         let args = ["aliasx", "run", "task", "--index", "0"];
-        let cli = Cli::try_parse_from(&args).unwrap();
+        let cli = Cli::try_parse_from(args).unwrap();
 
         match &cli.command {
-            Some(Commands::Run { id, task_options }) => {
+            Some(Commands::Run {
+                id, task_options, ..
+            }) => {
                 assert_eq!(id.as_ref().unwrap(), "task");
                 assert_eq!(task_options.index.unwrap(), 0);
             }
@@ -455,4 +1062,79 @@ mod tests {
         let res = run(&cli);
         assert!(res.is_err());
     }
+
+    #[test]
+    fn test_history_promote_index() {
+        let args = ["aliasx", "history", "promote", "--index", "3"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::History {
+                action: Some(HistoryCommands::Promote { index }),
+                ..
+            }) => assert_eq!(index, Some(3)),
+            _ => panic!("wrong subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_history_index_before_promote() {
+        let args = ["aliasx", "history", "-i", "3", "promote"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::History {
+                action: Some(HistoryCommands::Promote { index: None }),
+                task_options,
+                ..
+            }) => assert_eq!(task_options.index, Some(3)),
+            _ => panic!("wrong subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_history_without_action() {
+        let args = ["aliasx", "history", "-i", "1"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::History {
+                action,
+                task_options,
+                ..
+            }) => {
+                assert!(action.is_none());
+                assert_eq!(task_options.index, Some(1));
+            }
+            _ => panic!("wrong subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_edit_with_id() {
+        let args = ["aliasx", "edit", "build"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::Edit { id, task_options }) => {
+                assert_eq!(id.as_deref(), Some("build"));
+                assert!(task_options.index.is_none());
+            }
+            _ => panic!("wrong subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_remove_alias_rm() {
+        let args = ["aliasx", "rm", "-i", "4"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Commands::Remove { id, task_options }) => {
+                assert!(id.is_none());
+                assert_eq!(task_options.index, Some(4));
+            }
+            _ => panic!("wrong subcommand"),
+        }
+    }
 }
@@ -1,4 +1,5 @@
 pub mod cli;
+mod wizard;
 
 pub fn parse_and_run() -> anyhow::Result<()> {
     cli::parse_and_run()
@@ -0,0 +1,261 @@
+use aliasx_core::{
+    config_editor::ConfigEditor,
+    history::HistoryEntry,
+    input::{Input, InputOption},
+    input_mapping::InputMapping,
+    task_filter::TaskFilter,
+    task_reader,
+    tasks::{self, TaskEntry, Tasks},
+};
+use aliasx_tui::{fuzzy_finder, text_input, FuzzyConfig, TextInputConfig, TuiSession};
+use std::path::PathBuf;
+
+fn prompt_required(
+    prompt: &str,
+    hint: Option<&str>,
+    initial_value: &str,
+    session: &mut TuiSession,
+) -> anyhow::Result<String> {
+    let value = text_input(
+        prompt,
+        TextInputConfig {
+            hint: hint.map(str::to_string),
+            initial_value: initial_value.to_string(),
+            ..Default::default()
+        },
+        session,
+    )?;
+
+    if value.trim().is_empty() {
+        return Err(anyhow::anyhow!("{} is required", prompt));
+    }
+
+    Ok(value.trim().to_string())
+}
+
+fn prompt_id(prompt: &str, session: &mut TuiSession) -> anyhow::Result<Option<String>> {
+    let id = text_input(
+        prompt,
+        TextInputConfig {
+            hint: Some("optional - used with `aliasx run <id>`".to_string()),
+            ..Default::default()
+        },
+        session,
+    )?;
+
+    Ok((!id.trim().is_empty()).then(|| id.trim().to_string()))
+}
+
+fn prompt_yes_no(prompt: &str, session: &mut TuiSession) -> anyhow::Result<bool> {
+    let idx = fuzzy_finder(
+        &["yes".to_string(), "no".to_string()],
+        prompt,
+        FuzzyConfig {
+            has_details: false,
+            ..Default::default()
+        },
+        session,
+    )?;
+
+    Ok(idx == 0)
+}
+
+/// Asks for the scope and returns the config file the task will be added to.
+fn prompt_target(prompt: &str, session: &mut TuiSession) -> anyhow::Result<PathBuf> {
+    let scopes = [TaskFilter::Local, TaskFilter::Global];
+    let scope_names: Vec<String> = scopes.iter().map(|s| s.to_string()).collect();
+    let scope_idx = fuzzy_finder(
+        &scope_names,
+        prompt,
+        FuzzyConfig {
+            has_details: false,
+            ..Default::default()
+        },
+        session,
+    )?;
+
+    ConfigEditor::target_path(scopes[scope_idx])
+}
+
+/// The config at `path`, empty if it doesn't exist yet.
+fn load_target(path: &PathBuf) -> anyhow::Result<Tasks> {
+    if path.is_file() {
+        task_reader::parse_file(path)
+    } else {
+        Ok(Tasks::default())
+    }
+}
+
+/// Collects the definitions for inputs used in `command` that aren't defined in `target` yet.
+/// The definitions in `copied` are added as they are, the options for all others are prompted.
+fn prompt_missing_inputs(
+    prompt: &str,
+    command: &str,
+    target: &Tasks,
+    copied: Vec<Input>,
+    session: &mut TuiSession,
+) -> anyhow::Result<Vec<Input>> {
+    let mut inputs = copied;
+    for input_id in Input::extract_variables(command) {
+        if target.get_input(&input_id).is_ok() || inputs.iter().any(|i| i.id == input_id) {
+            continue;
+        }
+
+        let options = text_input(
+            &format!("{} | options for input '{}':", prompt, input_id),
+            TextInputConfig {
+                hint: Some(
+                    "comma separated, eg: debug, release - leave empty for free text".to_string(),
+                ),
+                ..Default::default()
+            },
+            session,
+        )?;
+
+        let options: Vec<InputOption> = options
+            .split(',')
+            .map(str::trim)
+            .filter(|o| !o.is_empty())
+            .map(InputOption::from)
+            .collect();
+
+        // no options makes it a free-text input
+        inputs.push(Input {
+            id: input_id,
+            options,
+            ..Default::default()
+        });
+    }
+
+    Ok(inputs)
+}
+
+fn append_task(
+    path: &PathBuf,
+    task: &TaskEntry,
+    inputs: &[Input],
+    mappings: &[InputMapping],
+) -> anyhow::Result<()> {
+    let report = ConfigEditor::append_task(path, task, inputs, mappings)?;
+    println!("Added '{}' to {}\n", task.label, path.display());
+    report.print(true);
+
+    Ok(())
+}
+
+pub fn run_add_wizard() -> anyhow::Result<()> {
+    let mut session = TuiSession::new()?;
+
+    let label = prompt_required("add | label:", None, "", &mut session)?;
+    let id = prompt_id("add | id:", &mut session)?;
+    let command = prompt_required(
+        "add | command:",
+        Some("reference inputs with ${input:<id>}"),
+        "",
+        &mut session,
+    )?;
+
+    let path = prompt_target("add | scope:", &mut session)?;
+    let target = load_target(&path)?;
+    let inputs = prompt_missing_inputs("add", &command, &target, vec![], &mut session)?;
+
+    drop(session);
+
+    let task = TaskEntry {
+        label,
+        command,
+        id,
+        conditions: None,
+        line: None,
+    };
+
+    append_task(&path, &task, &inputs, &[])
+}
+
+/// Turns a history entry into a new task. The command is built from the task the entry
+/// was created from if it still exists - its inputs can be kept as `${input:<id>}`
+/// placeholders or filled in with the values of the entry. The mappings and inputs the command
+/// uses are copied along when the task is added to another config.
+pub fn run_promote_wizard(entry: &HistoryEntry) -> anyhow::Result<()> {
+    let tasks = tasks::get_all_tasks(TaskFilter::All, false, None, false)?;
+    let indexed_tasks = tasks.indexed_tasks();
+    let original = indexed_tasks
+        .iter()
+        .find(|itask| itask.task.label == entry.task_name && itask.source.scope == entry.scope);
+
+    let mut session = TuiSession::new()?;
+
+    let label = prompt_required("promote | label:", None, &entry.task_name, &mut session)?;
+    let id = prompt_id("promote | id:", &mut session)?;
+
+    let command = match original {
+        Some(itask) => {
+            let inputs = itask
+                .source
+                .required_inputs_for_command(&itask.task.command)?;
+
+            // secrets aren't stored in the history, they always stay placeholders
+            let mut keep: Vec<&str> = Vec::new();
+            for input in &inputs {
+                let value = entry.input_selections.get(&input.id);
+                let keep_input = match value {
+                    Some(value) if !input.is_secret() => prompt_yes_no(
+                        &format!(
+                            "promote | replace '{}' with ${{input:{}}}?",
+                            value, input.id
+                        ),
+                        &mut session,
+                    )?,
+                    _ => true,
+                };
+                if keep_input {
+                    keep.push(&input.id);
+                }
+            }
+
+            entry.promoted_command(itask.source, &itask.task.command, &keep)?
+        }
+        None => entry.task_command.clone(),
+    };
+
+    let command = prompt_required(
+        "promote | command:",
+        Some("reference inputs with ${input:<id>}"),
+        &command,
+        &mut session,
+    )?;
+
+    let path = prompt_target("promote | scope:", &mut session)?;
+    let target = load_target(&path)?;
+    let (copied, mappings) = match original {
+        Some(itask) => itask.source.definitions_missing_in(&command, &target)?,
+        None => {
+            // without the original config there is nothing to copy the mappings from
+            if let Some(map_id) = InputMapping::extract_from_str(&command)
+                .into_iter()
+                .find(|map_id| target.resolve_mapping(map_id).is_err())
+            {
+                return Err(anyhow::anyhow!(
+                    "mapping '{}' isn't defined in {} and the original task is gone",
+                    map_id,
+                    path.display()
+                ));
+            }
+            (vec![], vec![])
+        }
+    };
+
+    let inputs = prompt_missing_inputs("promote", &command, &target, copied, &mut session)?;
+
+    drop(session);
+
+    let task = TaskEntry {
+        label,
+        command,
+        id,
+        conditions: None,
+        line: None,
+    };
+
+    append_task(&path, &task, &inputs, &mappings)
+}
@@ -0,0 +1,158 @@
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::aliases::ShellKind;
+use crate::tasks::Tasks;
+
+/// Cached aliases are reloaded at least once a day, even if no rc file changed,
+/// to pick up aliases sourced from files we don't track.
+const CACHE_TTL_HOURS: i64 = 24;
+
+/// Parsed native aliases stored in aliasx' data dir.
+/// The cache is invalidated when any of the shell's rc files change or when it expires.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AliasCache {
+    shell: String,
+    functions: bool,
+    created_at: DateTime<Utc>,
+
+    /// rc file -> mtime (seconds since epoch) at the time of caching
+    rc_files: IndexMap<String, Option<u64>>,
+
+    pub tasks: Tasks,
+}
+
+impl AliasCache {
+    fn get_path() -> anyhow::Result<PathBuf> {
+        let path = dirs::data_local_dir()
+            .context("could not find local data directory")?
+            .join("aliasx")
+            .join("native_aliases.json");
+
+        Ok(path)
+    }
+
+    fn current_rc_files(shell: &str) -> IndexMap<String, Option<u64>> {
+        let Some(home) = dirs::home_dir() else {
+            return IndexMap::new();
+        };
+
+        ShellKind::from_path(shell)
+            .rc_files(&home)
+            .iter()
+            .map(|path| (path.to_string_lossy().to_string(), Self::mtime(path)))
+            .collect()
+    }
+
+    fn mtime(path: &Path) -> Option<u64> {
+        std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+    }
+
+    fn is_valid(
+        &self,
+        shell: &str,
+        functions: bool,
+        rc_files: &IndexMap<String, Option<u64>>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        self.shell == shell
+            && self.functions == functions
+            && self.rc_files == *rc_files
+            && now - self.created_at < Duration::hours(CACHE_TTL_HOURS)
+    }
+
+    /// Returns the cached aliases if they are still valid for `shell`.
+    pub fn load(shell: &str, functions: bool) -> Option<Tasks> {
+        let path = Self::get_path().ok()?;
+        let content = std::fs::read_to_string(path).ok()?;
+        let cache: AliasCache = serde_json::from_str(&content).ok()?;
+
+        cache
+            .is_valid(shell, functions, &Self::current_rc_files(shell), Utc::now())
+            .then_some(cache.tasks)
+    }
+
+    pub fn new(shell: &str, functions: bool, tasks: Tasks) -> Self {
+        Self {
+            shell: shell.to_string(),
+            functions,
+            created_at: Utc::now(),
+            rc_files: Self::current_rc_files(shell),
+            tasks,
+        }
+    }
+
+    pub fn store(&self) -> anyhow::Result<()> {
+        let path = Self::get_path()?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(path, serde_json::to_string(self)?)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_cache(created_at: DateTime<Utc>) -> AliasCache {
+        AliasCache {
+            shell: "/bin/bash".to_string(),
+            functions: false,
+            created_at,
+            rc_files: IndexMap::from([("/home/user/.bashrc".to_string(), Some(100))]),
+            tasks: Tasks::default(),
+        }
+    }
+
+    #[test]
+    fn test_cache_valid() {
+        let now = Utc::now();
+        let cache = create_test_cache(now);
+        assert!(cache.is_valid("/bin/bash", false, &cache.rc_files, now));
+    }
+
+    #[test]
+    fn test_cache_invalid_rc_changed() {
+        let now = Utc::now();
+        let cache = create_test_cache(now);
+        let rc_files = IndexMap::from([("/home/user/.bashrc".to_string(), Some(200))]);
+        assert!(!cache.is_valid("/bin/bash", false, &rc_files, now));
+    }
+
+    #[test]
+    fn test_cache_invalid_rc_removed() {
+        let now = Utc::now();
+        let cache = create_test_cache(now);
+        let rc_files = IndexMap::from([("/home/user/.bashrc".to_string(), None)]);
+        assert!(!cache.is_valid("/bin/bash", false, &rc_files, now));
+    }
+
+    #[test]
+    fn test_cache_invalid_expired() {
+        let now = Utc::now();
+        let cache = create_test_cache(now - Duration::hours(CACHE_TTL_HOURS + 1));
+        assert!(!cache.is_valid("/bin/bash", false, &cache.rc_files, now));
+    }
+
+    #[test]
+    fn test_cache_invalid_other_shell() {
+        let now = Utc::now();
+        let cache = create_test_cache(now);
+        assert!(!cache.is_valid("/bin/zsh", false, &cache.rc_files, now));
+        assert!(!cache.is_valid("/bin/bash", true, &cache.rc_files, now));
+    }
+}
@@ -1,36 +1,277 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use execute::Execute;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use strum::Display;
 
+use crate::alias_cache::AliasCache;
 use crate::task_collection::TaskCollection;
+use crate::task_filter::TaskFilter;
 use crate::tasks::{TaskEntry, Tasks};
 
-fn parse_aliases(output: &str) -> Result<Tasks> {
-    let mut tasks = Tasks::default();
+/// The shells aliasx knows how to read native aliases and functions from.
+/// Anything that isn't zsh or fish is treated as a POSIX/bash-like shell.
+#[derive(Display, Debug, PartialEq, Clone, Copy)]
+#[strum(serialize_all = "lowercase")]
+pub enum ShellKind {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl ShellKind {
+    pub fn from_path(path: &str) -> Self {
+        match Path::new(path).file_name().and_then(|n| n.to_str()) {
+            Some("zsh") => Self::Zsh,
+            Some("fish") => Self::Fish,
+            _ => Self::Bash,
+        }
+    }
+
+    /// The rc files that can define aliases and functions for this shell.
+    /// Directories are included as their mtime changes when files are added or removed.
+    pub fn rc_files(self, home: &Path) -> Vec<PathBuf> {
+        let files: &[&str] = match self {
+            Self::Bash => &[".bashrc", ".bash_profile", ".bash_aliases", ".profile"],
+            Self::Zsh => &[".zshrc", ".zshenv", ".zprofile", ".zsh_aliases"],
+            Self::Fish => &[
+                ".config/fish/config.fish",
+                ".config/fish/functions",
+                ".config/fish/conf.d",
+            ],
+        };
+
+        files.iter().map(|file| home.join(file)).collect()
+    }
+
+    fn functions_command(self) -> &'static str {
+        match self {
+            Self::Bash => "declare -F",
+            Self::Zsh => "print -rl -- ${(ok)functions}",
+            Self::Fish => "functions -n",
+        }
+    }
+
+    /// Parses the output of `alias` into `(name, command)` pairs.
+    pub fn parse_aliases(self, output: &str) -> Vec<(String, String)> {
+        output
+            .lines()
+            .filter_map(|line| match self {
+                Self::Bash => parse_bash_alias(line),
+                Self::Zsh => parse_zsh_alias(line),
+                Self::Fish => parse_fish_alias(line),
+            })
+            .collect()
+    }
+
+    /// Parses the output of `functions_command` into function names.
+    /// Private helpers (prefixed with `_`) are skipped.
+    pub fn parse_functions(self, output: &str) -> Vec<String> {
+        let names: Vec<&str> = match self {
+            // declare -f name / declare -fx name
+            Self::Bash => output
+                .lines()
+                .filter_map(|line| line.strip_prefix("declare -f"))
+                .filter_map(|rest| rest.split_whitespace().last())
+                .collect(),
+            Self::Zsh => output.lines().map(str::trim).collect(),
+            // fish separates names with commas when printing to a terminal
+            Self::Fish => output
+                .lines()
+                .flat_map(|line| line.split(','))
+                .map(str::trim)
+                .collect(),
+        };
+
+        names
+            .into_iter()
+            .filter(|name| !name.is_empty() && !name.starts_with('_'))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+// bash: alias name='value'
+fn parse_bash_alias(line: &str) -> Option<(String, String)> {
+    let stripped = line.strip_prefix("alias ")?;
+    let stripped = stripped.strip_prefix("-- ").unwrap_or(stripped);
+    let (name, cmd) = stripped.split_once('=')?;
+
+    Some((unquote_posix(name.trim()), unquote_posix(cmd.trim())))
+}
+
+// zsh: name=value (only quoted if needed)
+fn parse_zsh_alias(line: &str) -> Option<(String, String)> {
+    let (name, cmd) = line.split_once('=')?;
+    let name = unquote_posix(name.trim());
+
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
 
-    for line in output.lines() {
-        // only parse lines starting with "alias "
-        if let Some(stripped) = line.strip_prefix("alias ") {
-            if let Some((name, cmd)) = stripped.split_once('=') {
-                let task = TaskEntry {
-                    label: name.trim().to_string(),
-                    command: cmd.trim_matches('\'').to_string(),
-                    id: Option::None,
-                    conditions: Option::None,
-                };
-                tasks.tasks.insert(task);
+    Some((name, unquote_posix(cmd.trim())))
+}
+
+// fish: alias name 'value' (value escaped with `string escape`)
+fn parse_fish_alias(line: &str) -> Option<(String, String)> {
+    let stripped = line.strip_prefix("alias ")?.trim_start();
+    let (name, cmd) = stripped
+        .split_once(char::is_whitespace)
+        .or_else(|| stripped.split_once('='))?;
+
+    Some((unquote_fish(name.trim()), unquote_fish(cmd.trim())))
+}
+
+/// Unquotes a word as produced by bash/zsh, supporting single quotes
+/// (incl. the `'\''` idiom), double quotes, `$'...'` and backslash escapes.
+fn unquote_posix(s: &str) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    out.push(c);
+                }
             }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\'' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => out.push('\n'),
+                            Some('t') => out.push('\t'),
+                            Some(c) => out.push(c),
+                            None => out.push('\\'),
+                        },
+                        c => out.push(c),
+                    }
+                }
+            }
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.peek() {
+                            Some(&n) if matches!(n, '"' | '\\' | '$' | '`') => {
+                                out.push(n);
+                                chars.next();
+                            }
+                            _ => out.push('\\'),
+                        },
+                        c => out.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    out.push(c);
+                }
+            }
+            c => out.push(c),
         }
     }
 
-    Ok(tasks)
+    out
 }
 
-pub fn get_aliases_as_tasks() -> anyhow::Result<TaskCollection> {
-    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".into());
+/// Unquotes a word as produced by fish's `string escape`.
+fn unquote_fish(s: &str) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\'' => break,
+                        '\\' => match chars.next() {
+                            Some(n) if matches!(n, '\'' | '\\') => out.push(n),
+                            Some(n) => {
+                                out.push('\\');
+                                out.push(n);
+                            }
+                            None => out.push('\\'),
+                        },
+                        c => out.push(c),
+                    }
+                }
+            }
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some(n) if matches!(n, '"' | '\\' | '$') => out.push(n),
+                            Some(n) => {
+                                out.push('\\');
+                                out.push(n);
+                            }
+                            None => out.push('\\'),
+                        },
+                        c => out.push(c),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(c) => out.push(c),
+                None => {}
+            },
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+fn parse_aliases(shell: &str, aliases: &str, functions: Option<&str>) -> Tasks {
+    let kind = ShellKind::from_path(shell);
+    let mut tasks = Tasks::default();
+
+    let alias_names: Vec<(String, String)> = kind.parse_aliases(aliases);
+
+    for (name, cmd) in alias_names.iter() {
+        tasks.tasks.insert(TaskEntry {
+            label: name.clone(),
+            command: cmd.clone(),
+            id: Option::None,
+            conditions: Option::None,
+            line: Option::None,
+        });
+    }
 
+    // functions only exist inside the interactive shell so they are invoked through it
+    if let Some(functions) = functions {
+        for name in kind.parse_functions(functions) {
+            // fish defines its aliases as functions
+            if alias_names.iter().any(|(alias, _)| *alias == name) {
+                continue;
+            }
+
+            tasks.tasks.insert(TaskEntry {
+                command: format!("{} -ic {}", shell, TaskCollection::quote_arg(&name)),
+                label: name,
+                id: Option::None,
+                conditions: Option::None,
+                line: Option::None,
+            });
+        }
+    }
+
+    tasks
+}
+
+fn run_interactive(shell: &str, shell_command: &str) -> Result<String> {
     let mut command = Command::new(shell);
-    command.args(["-ic", "alias"]);
+    command.args(["-ic", shell_command]);
     command.stdout(Stdio::piped());
 
     let output = command.execute_output()?;
@@ -38,16 +279,164 @@ pub fn get_aliases_as_tasks() -> anyhow::Result<TaskCollection> {
     match output.status.code() {
         Some(0) => {}
         Some(code) => {
-            return Err(anyhow::anyhow!(
-                "calling 'alias' failed (exit code {})",
+            return Err(anyhow!(
+                "calling '{}' failed (exit code {})",
+                shell_command,
                 code
             ))
         }
-        None => return Err(anyhow::anyhow!("calling 'alias' was interrupted")),
+        None => return Err(anyhow!("calling '{}' was interrupted", shell_command)),
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Options for loading native aliases.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NativeOptions {
+    /// include shell functions as tasks
+    pub functions: bool,
+
+    /// reload the aliases instead of using the cache
+    pub refresh: bool,
+}
+
+/// Returns the native aliases as tasks - served from the cache unless `refresh`
+/// is set or the cache has been invalidated.
+pub fn get_aliases(options: NativeOptions) -> anyhow::Result<Tasks> {
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".into());
+
+    let mut tasks = match AliasCache::load(&shell, options.functions) {
+        Some(tasks) if !options.refresh => tasks,
+        _ => load_aliases(&shell, options.functions)?,
+    };
+    tasks.scope = TaskFilter::Native;
+
+    Ok(tasks)
+}
+
+fn load_aliases(shell: &str, include_functions: bool) -> anyhow::Result<Tasks> {
+    let aliases = run_interactive(shell, "alias")?;
+    let functions = if include_functions {
+        let kind = ShellKind::from_path(shell);
+        Some(run_interactive(shell, kind.functions_command())?)
+    } else {
+        None
+    };
+
+    let tasks = parse_aliases(shell, &aliases, functions.as_deref());
+    let cache = AliasCache::new(shell, include_functions, tasks);
+
+    // a failing cache should never prevent the aliases from being used
+    let _ = cache.store();
+
+    Ok(cache.tasks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(name: &str, cmd: &str) -> (String, String) {
+        (name.to_string(), cmd.to_string())
+    }
+
+    #[test]
+    fn test_shell_kind_from_path() {
+        assert_eq!(ShellKind::from_path("/bin/bash"), ShellKind::Bash);
+        assert_eq!(ShellKind::from_path("/usr/bin/zsh"), ShellKind::Zsh);
+        assert_eq!(ShellKind::from_path("/usr/local/bin/fish"), ShellKind::Fish);
+        assert_eq!(ShellKind::from_path("/bin/sh"), ShellKind::Bash);
+    }
+
+    #[test]
+    fn test_parse_bash_aliases() {
+        let output = "alias ll='ls -alF'\nalias gs='git status'\nsome rc noise\n";
+        assert_eq!(
+            ShellKind::Bash.parse_aliases(output),
+            vec![pair("ll", "ls -alF"), pair("gs", "git status")]
+        );
+    }
+
+    #[test]
+    fn test_parse_bash_alias_escaped_quote() {
+        let output = r#"alias hi='echo '\''hello world'\'''"#;
+        assert_eq!(
+            ShellKind::Bash.parse_aliases(output),
+            vec![pair("hi", "echo 'hello world'")]
+        );
     }
 
-    let output_str = String::from_utf8(output.stdout)?;
-    let aliases = parse_aliases(&output_str)?;
+    #[test]
+    fn test_parse_zsh_aliases() {
+        let output = "ll='ls -alF'\ng=git\n'...'='cd ../..'\nnl=$'echo a\\nb'\n";
+        assert_eq!(
+            ShellKind::Zsh.parse_aliases(output),
+            vec![
+                pair("ll", "ls -alF"),
+                pair("g", "git"),
+                pair("...", "cd ../.."),
+                pair("nl", "echo a\nb"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_zsh_alias_escaped_quote() {
+        let output = r#"hi='echo '\''hi'\'''"#;
+        assert_eq!(
+            ShellKind::Zsh.parse_aliases(output),
+            vec![pair("hi", "echo 'hi'")]
+        );
+    }
 
-    Ok(TaskCollection::new(vec![aliases]))
+    #[test]
+    fn test_parse_fish_aliases() {
+        let output = "alias g git\nalias ll 'ls -lh'\nalias hi 'echo \\'hi\\''\n";
+        assert_eq!(
+            ShellKind::Fish.parse_aliases(output),
+            vec![
+                pair("g", "git"),
+                pair("ll", "ls -lh"),
+                pair("hi", "echo 'hi'")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_bash_functions() {
+        let output = "declare -f deploy\ndeclare -fx _completion\ndeclare -fx mkcd\n";
+        assert_eq!(
+            ShellKind::Bash.parse_functions(output),
+            vec!["deploy", "mkcd"]
+        );
+    }
+
+    #[test]
+    fn test_parse_fish_functions() {
+        assert_eq!(
+            ShellKind::Fish.parse_functions("fish_prompt, __fish_x, mkcd\n"),
+            vec!["fish_prompt", "mkcd"]
+        );
+        assert_eq!(
+            ShellKind::Fish.parse_functions("fish_prompt\nmkcd\n"),
+            vec!["fish_prompt", "mkcd"]
+        );
+    }
+
+    #[test]
+    fn test_parse_aliases_with_functions() {
+        let tasks = parse_aliases("/usr/bin/fish", "alias ll 'ls -lh'\n", Some("ll\nmkcd\n"));
+
+        let commands: Vec<&str> = tasks.tasks.iter().map(|t| t.command.as_str()).collect();
+        assert_eq!(commands, vec!["ls -lh", "/usr/bin/fish -ic 'mkcd'"]);
+    }
+
+    #[test]
+    fn test_parse_functions_with_quote() {
+        let tasks = parse_aliases("/bin/zsh", "", Some("it's\n"));
+
+        let commands: Vec<&str> = tasks.tasks.iter().map(|t| t.command.as_str()).collect();
+        assert_eq!(commands, vec![r"/bin/zsh -ic 'it'\''s'"]);
+    }
 }
@@ -0,0 +1,1751 @@
+use anyhow::{anyhow, Context};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Range;
+use std::path::Path;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+use crate::task_reader::TaskFormat;
+
+/// A config file that is edited as text.
+///
+/// [`ConfigDocument::root`] only records where values are written, edits splice new
+/// text into the original so comments, layout and fields unknown to aliasx are kept.
+pub struct ConfigDocument {
+    format: TaskFormat,
+    content: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// strings without their quotes, anything else as written
+    Scalar(String),
+    Seq(Vec<Node>),
+    Map(Vec<Entry>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub value: Value,
+
+    /// line the node starts on, counting from 1
+    pub line: usize,
+
+    /// bytes of the node - yaml block collections end where the next token starts
+    span: Range<usize>,
+
+    /// written as a flow collection, eg. `[a, b]` in yaml
+    flow: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub key: String,
+
+    /// line of the key
+    pub line: usize,
+
+    pub value: Node,
+}
+
+impl Node {
+    fn new(value: Value, span: Range<usize>, line: usize) -> Self {
+        Self {
+            value,
+            line,
+            span,
+            flow: false,
+        }
+    }
+
+    /// Returns the value of `key` if the node is a mapping.
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        self.entry(key).map(|entry| &entry.value)
+    }
+
+    pub fn items(&self) -> Option<&[Node]> {
+        match &self.value {
+            Value::Seq(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            Value::Scalar(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn entry(&self, key: &str) -> Option<&Entry> {
+        match &self.value {
+            Value::Map(entries) => entries.iter().find(|entry| entry.key == key),
+            _ => None,
+        }
+    }
+
+    fn is_null(&self, content: &str) -> bool {
+        matches!(self.value, Value::Scalar(_))
+            && matches!(
+                &content[self.span.clone()],
+                "" | "~" | "null" | "Null" | "NULL"
+            )
+    }
+
+    /// The node written last, new elements are inserted after its line.
+    fn last_leaf(&self) -> &Node {
+        let last = match &self.value {
+            Value::Seq(items) => items.last(),
+            Value::Map(entries) => entries.last().map(|entry| &entry.value),
+            Value::Scalar(_) => None,
+        };
+
+        match last {
+            Some(node) if !self.flow => node.last_leaf(),
+            _ => self,
+        }
+    }
+}
+
+/// A comment of the source document without its markers.
+struct Comment {
+    line: usize,
+    end_line: usize,
+    text: Vec<String>,
+
+    /// written after a value instead of on its own line
+    trailing: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+impl ConfigDocument {
+    pub fn new(content: impl Into<String>, format: TaskFormat) -> Self {
+        Self {
+            format,
+            content: content.into(),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let format = TaskFormat::from_path(path)
+            .with_context(|| format!("unsupported file format: {:?}", path))?;
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config: {:?}", path))?;
+
+        Ok(Self::new(content, format))
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Parses the document into a tree of values and their positions.
+    pub fn root(&self) -> anyhow::Result<Node> {
+        Ok(self.parse()?.0)
+    }
+
+    fn parse(&self) -> anyhow::Result<(Node, Vec<Comment>)> {
+        match self.format {
+            TaskFormat::Yaml => YamlBuilder::parse(&self.content),
+            TaskFormat::Json => JsonScanner::parse(&self.content),
+        }
+    }
+
+    /// Writes the document in `format`, keeping comments, key order and unknown fields.
+    ///
+    /// yaml doesn't distinguish `2` and `"2"` for string fields, so scalars are written
+    /// with the type they have in `model` - the deserialized document.
+    pub fn convert<T: Serialize>(&self, format: TaskFormat, model: &T) -> anyhow::Result<String> {
+        let mut value: serde_yaml::Value = match self.format {
+            TaskFormat::Yaml => serde_yaml::from_str(&self.content)?,
+            TaskFormat::Json => serde_json5::from_str(&self.content)?,
+        };
+        coerce(&mut value, &serde_json::to_value(model)?);
+
+        let output = match format {
+            TaskFormat::Yaml => serde_yaml::to_string(&value)?,
+            TaskFormat::Json => serde_json::to_string_pretty(&value)? + "\n",
+        };
+
+        let (source, comments) = self.parse()?;
+        let blank_lines = self
+            .content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| line.trim().is_empty())
+            .map(|(idx, _)| idx + 1)
+            .collect();
+
+        attach_comments(
+            &source,
+            comments,
+            &blank_lines,
+            &ConfigDocument::new(output, format),
+        )
+    }
+
+    /// Appends `item` to the top-level sequence `key`, creating the sequence if needed.
+    pub fn push_item<T: Serialize>(&mut self, key: &str, item: &T) -> anyhow::Result<()> {
+        let root = self.root()?;
+        if root.flow {
+            return Err(anyhow!("configs written in flow style can't be edited"));
+        }
+
+        self.content = match self.format {
+            TaskFormat::Yaml => insert_yaml_item(&self.content, &root, key, item)?,
+            TaskFormat::Json => insert_json_item(&self.content, &root, key, item)?,
+        };
+
+        Ok(())
+    }
+
+    /// Removes item `idx` of the top-level sequence `key` and the comment lines directly
+    /// above it.
+    pub fn remove_item(&mut self, key: &str, idx: usize) -> anyhow::Result<()> {
+        let (root, comments) = self.parse()?;
+        if root.flow {
+            return Err(anyhow!("configs written in flow style can't be edited"));
+        }
+
+        let entry = root
+            .entry(key)
+            .ok_or_else(|| anyhow!("'{}' isn't defined", key))?;
+        let items = entry
+            .value
+            .items()
+            .ok_or_else(|| anyhow!("'{}' must be a sequence", key))?;
+        if idx >= items.len() {
+            return Err(anyhow!("'{}' has no item {}", key, idx));
+        }
+
+        // comment lines by the line they end on
+        let leading = comments
+            .iter()
+            .filter(|comment| !comment.trailing)
+            .map(|comment| (comment.end_line, comment.line))
+            .collect();
+
+        self.content = match self.format {
+            TaskFormat::Yaml if !entry.value.flow => {
+                remove_yaml_item(&self.content, entry, idx, &leading)
+            }
+            _ => remove_element(&self.content, items, idx, &leading),
+        };
+
+        Ok(())
+    }
+}
+
+/// Turns scalars into strings where `model` has a string at the same position.
+fn coerce(value: &mut serde_yaml::Value, model: &serde_json::Value) {
+    use serde_yaml::Value as Yaml;
+
+    match (value, model) {
+        (Yaml::Mapping(mapping), serde_json::Value::Object(fields)) => {
+            for (key, value) in mapping.iter_mut() {
+                if let Some(field) = key.as_str().and_then(|key| fields.get(key)) {
+                    coerce(value, field);
+                }
+            }
+        }
+        (Yaml::Sequence(items), serde_json::Value::Array(elements)) => {
+            for (item, element) in items.iter_mut().zip(elements) {
+                coerce(item, element);
+            }
+        }
+        (value, serde_json::Value::String(_)) => {
+            let text = match &*value {
+                Yaml::Number(number) => number.to_string(),
+                Yaml::Bool(b) => b.to_string(),
+                _ => return,
+            };
+            *value = Yaml::String(text);
+        }
+        _ => {}
+    }
+}
+
+/// Returns the path and line of every map entry and sequence element in document order.
+fn anchors(node: &Node, path: &mut Vec<Segment>, out: &mut Vec<(Vec<Segment>, usize)>) {
+    match &node.value {
+        Value::Map(entries) => {
+            for entry in entries {
+                path.push(Segment::Key(entry.key.clone()));
+                out.push((path.clone(), entry.line));
+                anchors(&entry.value, path, out);
+                path.pop();
+            }
+        }
+        Value::Seq(items) => {
+            for (idx, item) in items.iter().enumerate() {
+                path.push(Segment::Index(idx));
+                out.push((path.clone(), item.line));
+                anchors(item, path, out);
+                path.pop();
+            }
+        }
+        Value::Scalar(_) => {}
+    }
+}
+
+#[derive(Default)]
+struct Insert {
+    blank: bool,
+    before: Vec<String>,
+    trailing: Vec<String>,
+}
+
+/// Writes the comments of `source` into `target` next to the values they belong to.
+/// Comments on their own line belong to the next value, the others to the last value
+/// of their line.
+fn attach_comments(
+    source: &Node,
+    comments: Vec<Comment>,
+    blank_lines: &HashSet<usize>,
+    target: &ConfigDocument,
+) -> anyhow::Result<String> {
+    let mut source_anchors = vec![];
+    anchors(source, &mut vec![], &mut source_anchors);
+
+    let mut target_anchors = vec![];
+    anchors(&target.root()?, &mut vec![], &mut target_anchors);
+    let target_lines: HashMap<_, _> = target_anchors.into_iter().collect();
+
+    let mut attached: HashMap<usize, Insert> = HashMap::new();
+    let mut first_lines: HashMap<usize, usize> = HashMap::new();
+    let mut end = vec![];
+
+    for comment in comments {
+        let owner = if comment.trailing {
+            source_anchors
+                .iter()
+                .rposition(|(_, line)| *line == comment.line)
+        } else {
+            None
+        };
+
+        match owner {
+            Some(idx) => attached
+                .entry(idx)
+                .or_default()
+                .trailing
+                .extend(comment.text),
+            None => match source_anchors
+                .iter()
+                .position(|(_, line)| *line > comment.end_line)
+            {
+                Some(idx) => {
+                    attached.entry(idx).or_default().before.extend(comment.text);
+                    first_lines.entry(idx).or_insert(comment.line);
+                }
+                None => end.extend(comment.text),
+            },
+        }
+    }
+
+    let mut inserts: BTreeMap<usize, Insert> = BTreeMap::new();
+    for (idx, (path, line)) in source_anchors.iter().enumerate() {
+        let first_on_line = idx == 0 || source_anchors[idx - 1].1 != *line;
+        let first_line = first_lines.get(&idx).copied().unwrap_or(*line);
+        let blank = first_on_line && first_line > 1 && blank_lines.contains(&(first_line - 1));
+        let comments = attached.remove(&idx).unwrap_or_default();
+
+        match target_lines.get(path) {
+            Some(target_line) => {
+                let insert = inserts.entry(*target_line).or_default();
+                insert.blank |= blank;
+                insert.before.extend(comments.before);
+                insert.trailing.extend(comments.trailing);
+            }
+            None => {
+                end.extend(comments.before);
+                end.extend(comments.trailing);
+            }
+        }
+    }
+
+    let marker = match target.format {
+        TaskFormat::Yaml => "#",
+        TaskFormat::Json => "//",
+    };
+    let comment = |text: &str| match text {
+        "" => marker.to_string(),
+        text => format!("{} {}", marker, text),
+    };
+
+    let mut out = String::new();
+    for (idx, line) in target.content.lines().enumerate() {
+        if let Some(insert) = inserts.get(&(idx + 1)) {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            if insert.blank && idx > 0 {
+                out.push('\n');
+            }
+            for text in &insert.before {
+                out += &format!("{}{}\n", indent, comment(text));
+            }
+            out += line;
+            for text in &insert.trailing {
+                out += &format!(" {}", comment(text));
+            }
+        } else {
+            out += line;
+        }
+        out.push('\n');
+    }
+    for text in end {
+        out += &comment(&text);
+        out.push('\n');
+    }
+
+    Ok(out)
+}
+
+/// Start offset of every line.
+struct Lines(Vec<usize>);
+
+impl Lines {
+    fn new(content: &str) -> Self {
+        Self(
+            std::iter::once(0)
+                .chain(content.match_indices('\n').map(|(idx, _)| idx + 1))
+                .collect(),
+        )
+    }
+
+    fn line(&self, pos: usize) -> usize {
+        self.0.partition_point(|&start| start <= pos)
+    }
+
+    fn start(&self, line: usize) -> usize {
+        self.0[line - 1]
+    }
+}
+
+fn indentation(content: &str, pos: usize) -> &str {
+    let start = content[..pos].rfind('\n').map_or(0, |idx| idx + 1);
+    let line = &content[start..];
+
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Position after the end of the line containing `pos`, including its newline.
+fn next_line(content: &str, pos: usize) -> usize {
+    content[pos..]
+        .find('\n')
+        .map_or(content.len(), |idx| pos + idx + 1)
+}
+
+/// Builds the node tree of a yaml document from yaml-rust2's parser events.
+struct YamlBuilder<'a> {
+    content: &'a str,
+    lines: Lines,
+
+    /// byte offset of every char - markers count chars
+    offsets: Vec<usize>,
+
+    /// open collections and, for mappings, the key waiting for its value
+    stack: Vec<(Node, Option<(String, usize)>)>,
+    root: Option<Node>,
+
+    /// end of the last token of each line, only a comment can follow it
+    line_ends: HashMap<usize, usize>,
+
+    /// lines inside multi-line strings, a `#` there isn't a comment
+    inside_scalars: HashSet<usize>,
+
+    last_end: usize,
+    error: Option<anyhow::Error>,
+}
+
+impl<'a> YamlBuilder<'a> {
+    fn parse(content: &'a str) -> anyhow::Result<(Node, Vec<Comment>)> {
+        let mut builder = Self {
+            content,
+            lines: Lines::new(content),
+            offsets: content
+                .char_indices()
+                .map(|(idx, _)| idx)
+                .chain(std::iter::once(content.len()))
+                .collect(),
+            stack: vec![],
+            root: None,
+            line_ends: HashMap::new(),
+            inside_scalars: HashSet::new(),
+            last_end: 0,
+            error: None,
+        };
+
+        Parser::new_from_str(content)
+            .load(&mut builder, false)
+            .map_err(|err| anyhow!("invalid yaml: {}", err))?;
+        if let Some(err) = builder.error.take() {
+            return Err(err);
+        }
+
+        let root = builder
+            .root
+            .take()
+            .unwrap_or_else(|| Node::new(Value::Scalar(String::new()), 0..0, 1));
+        let comments = builder.comments();
+
+        Ok((root, comments))
+    }
+
+    fn end_token(&mut self, end: usize) {
+        let line = self.lines.line(end.saturating_sub(1));
+        let line_end = self.line_ends.entry(line).or_default();
+        *line_end = end.max(*line_end);
+        self.last_end = end;
+    }
+
+    fn scalar(&mut self, value: String, style: TScalarStyle, pos: usize) -> Node {
+        let in_flow = self.stack.last().is_some_and(|(node, _)| node.flow);
+        let bytes = self.content.as_bytes();
+
+        let end = match style {
+            // yaml-rust2 marks an empty value at the following token
+            TScalarStyle::Plain if value.is_empty() => {
+                let pos = self.last_end;
+                return Node::new(Value::Scalar(value), pos..pos, self.lines.line(pos));
+            }
+            TScalarStyle::Plain => {
+                let mut end = pos;
+                for (idx, &b) in bytes.iter().enumerate().skip(pos) {
+                    let next = bytes.get(idx + 1).copied().unwrap_or(b'\n');
+                    let flow_indicator = |b: u8| in_flow && b",[]{}".contains(&b);
+                    if b == b'\n'
+                        || (b == b'#' && idx > pos && bytes[idx - 1].is_ascii_whitespace())
+                        || (b == b':' && (next.is_ascii_whitespace() || flow_indicator(next)))
+                        || flow_indicator(b)
+                    {
+                        break;
+                    }
+                    if !b.is_ascii_whitespace() {
+                        end = idx + 1;
+                    }
+                }
+                end
+            }
+            TScalarStyle::SingleQuoted | TScalarStyle::DoubleQuoted => {
+                let quote = bytes[pos];
+                let mut idx = pos + 1;
+                while idx < bytes.len() {
+                    match bytes[idx] {
+                        b'\\' if quote == b'"' => idx += 1,
+                        b'\'' if quote == b'\'' && bytes.get(idx + 1) == Some(&b'\'') => idx += 1,
+                        b if b == quote => break,
+                        _ => {}
+                    }
+                    idx += 1;
+                }
+                (idx + 1).min(bytes.len())
+            }
+            TScalarStyle::Literal | TScalarStyle::Folded => return self.block_scalar(value, pos),
+        };
+
+        let (line, end_line) = (self.lines.line(pos), self.lines.line(end - 1));
+        self.inside_scalars.extend(line + 1..=end_line);
+        self.end_token(end);
+
+        Node::new(Value::Scalar(value), pos..end, line)
+    }
+
+    /// yaml-rust2 marks block scalars at their first content line, the node starts at
+    /// the `|` or `>` header.
+    fn block_scalar(&mut self, value: String, pos: usize) -> Node {
+        let header = self.content[..pos].rfind(['|', '>']).unwrap_or(pos);
+        let header_end = header
+            + 1
+            + self.content[header + 1..]
+                .find(|c: char| !matches!(c, '+' | '-' | '0'..='9'))
+                .unwrap_or(0);
+        self.end_token(header_end);
+
+        let header_line = self.lines.line(header);
+        let mut end = header_end;
+        if !value.is_empty() {
+            let body_indent = indentation(self.content, pos).len();
+            for (idx, text) in self.content[self.lines.start(header_line + 1)..]
+                .lines()
+                .enumerate()
+            {
+                let indent = text.len() - text.trim_start_matches(' ').len();
+                if !text.trim().is_empty() && indent < body_indent {
+                    break;
+                }
+
+                let line = header_line + 1 + idx;
+                self.inside_scalars.insert(line);
+                if !text.trim().is_empty() {
+                    end = self.lines.start(line) + text.len();
+                }
+            }
+        }
+        self.last_end = end;
+
+        Node::new(Value::Scalar(value), header..end, header_line)
+    }
+
+    fn open(&mut self, value: Value, pos: usize) {
+        let flow = matches!(self.content.as_bytes().get(pos), Some(b'[' | b'{'));
+        let mut node = Node::new(value, pos..pos, self.lines.line(pos));
+        node.flow = flow;
+        if flow {
+            self.end_token(pos + 1);
+        }
+
+        self.stack.push((node, None));
+    }
+
+    fn close(&mut self, pos: usize) {
+        let Some((mut node, _)) = self.stack.pop() else {
+            return;
+        };
+
+        node.span.end = if node.flow {
+            self.end_token(pos + 1);
+            pos + 1
+        } else {
+            pos.max(node.span.start)
+        };
+        self.push(node);
+    }
+
+    fn push(&mut self, node: Node) {
+        let Some((parent, key)) = self.stack.last_mut() else {
+            self.root.get_or_insert(node);
+            return;
+        };
+
+        // block collections are marked at their first value
+        let first = match &parent.value {
+            Value::Seq(items) => items.is_empty(),
+            Value::Map(entries) => entries.is_empty() && key.is_none(),
+            Value::Scalar(_) => false,
+        };
+        if first && !parent.flow {
+            parent.span.start = match parent.value {
+                Value::Seq(_) => self.content[..node.span.start]
+                    .trim_end()
+                    .strip_suffix('-')
+                    .map_or(node.span.start, str::len),
+                _ => node.span.start,
+            };
+            parent.line = self.lines.line(parent.span.start);
+        }
+
+        match &mut parent.value {
+            Value::Seq(items) => items.push(node),
+            Value::Map(entries) => match (key.take(), node.value) {
+                (Some((key, line)), value) => entries.push(Entry {
+                    key,
+                    line,
+                    value: Node { value, ..node },
+                }),
+                (None, Value::Scalar(name)) => *key = Some((name, node.line)),
+                (None, _) => {
+                    self.error = Some(anyhow!(
+                        "line {}: only scalar mapping keys are supported",
+                        node.line
+                    ))
+                }
+            },
+            Value::Scalar(_) => {}
+        }
+    }
+
+    fn comments(&self) -> Vec<Comment> {
+        let mut comments = vec![];
+
+        for (idx, text) in self.content.lines().enumerate() {
+            let line = idx + 1;
+            let comment = if let Some(comment) = text.trim_start().strip_prefix('#') {
+                (!self.inside_scalars.contains(&line)).then_some((comment, false))
+            } else {
+                self.line_ends.get(&line).and_then(|&from| {
+                    let to = self.lines.start(line) + text.len();
+                    let rest = &self.content[from.min(to)..to];
+                    rest.find('#').map(|idx| (&rest[idx + 1..], true))
+                })
+            };
+
+            if let Some((comment, trailing)) = comment {
+                comments.push(Comment {
+                    line,
+                    end_line: line,
+                    text: vec![comment.trim().to_string()],
+                    trailing,
+                });
+            }
+        }
+
+        comments
+    }
+}
+
+impl MarkedEventReceiver for YamlBuilder<'_> {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let pos = self.offsets[mark.index().min(self.offsets.len() - 1)];
+        match event {
+            Event::Scalar(value, style, ..) => {
+                let node = self.scalar(value, style, pos);
+                self.push(node);
+            }
+            Event::Alias(_) => {
+                let end = self.content[pos..]
+                    .find(|c: char| c.is_whitespace() || ",[]{}".contains(c))
+                    .map_or(self.content.len(), |idx| pos + idx);
+                self.end_token(end);
+                let alias = self.content[pos..end].to_string();
+                self.push(Node::new(
+                    Value::Scalar(alias),
+                    pos..end,
+                    self.lines.line(pos),
+                ));
+            }
+            Event::SequenceStart(..) => self.open(Value::Seq(vec![]), pos),
+            Event::MappingStart(..) => self.open(Value::Map(vec![]), pos),
+            Event::SequenceEnd | Event::MappingEnd => self.close(pos),
+            _ => {}
+        }
+    }
+}
+
+/// Reads the values and comments of a json5 document.
+struct JsonScanner<'a> {
+    content: &'a str,
+    lines: Lines,
+    pos: usize,
+    comments: Vec<Range<usize>>,
+}
+
+impl<'a> JsonScanner<'a> {
+    fn parse(content: &'a str) -> anyhow::Result<(Node, Vec<Comment>)> {
+        let mut scanner = Self {
+            content,
+            lines: Lines::new(content),
+            pos: 0,
+            comments: vec![],
+        };
+
+        let root = scanner.value()?;
+        scanner.skip_trivia()?;
+        if scanner.pos < content.len() {
+            return Err(scanner.error("unexpected content after the document"));
+        }
+
+        let comments = scanner.comments();
+        Ok((root, comments))
+    }
+
+    fn error(&self, msg: &str) -> anyhow::Error {
+        anyhow!(
+            "invalid json: {} at line {}",
+            msg,
+            self.lines.line(self.pos)
+        )
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.content.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_trivia(&mut self) -> anyhow::Result<()> {
+        loop {
+            let rest = &self.content[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+
+            let len = if trimmed.starts_with("//") {
+                trimmed.find('\n').unwrap_or(trimmed.len())
+            } else if trimmed.starts_with("/*") {
+                trimmed
+                    .find("*/")
+                    .ok_or_else(|| self.error("unterminated comment"))?
+                    + 2
+            } else {
+                return Ok(());
+            };
+
+            self.comments.push(self.pos..self.pos + len);
+            self.pos += len;
+        }
+    }
+
+    fn value(&mut self) -> anyhow::Result<Node> {
+        self.skip_trivia()?;
+        let start = self.pos;
+        let line = self.lines.line(start);
+
+        let value = match self.peek() {
+            Some(b'{') => {
+                let mut entries = vec![];
+                self.collection(b'}', |scanner| {
+                    let line = scanner.lines.line(scanner.pos);
+                    let key = match scanner.peek() {
+                        Some(b'"' | b'\'') => scanner.string()?,
+                        _ => scanner.bare()?,
+                    };
+                    scanner.skip_trivia()?;
+                    if scanner.peek() != Some(b':') {
+                        return Err(scanner.error("expected ':'"));
+                    }
+                    scanner.pos += 1;
+
+                    let value = scanner.value()?;
+                    entries.push(Entry { key, line, value });
+                    Ok(())
+                })?;
+                Value::Map(entries)
+            }
+            Some(b'[') => {
+                let mut items = vec![];
+                self.collection(b']', |scanner| {
+                    items.push(scanner.value()?);
+                    Ok(())
+                })?;
+                Value::Seq(items)
+            }
+            Some(b'"' | b'\'') => Value::Scalar(self.string()?),
+            Some(_) => Value::Scalar(self.bare()?),
+            None => return Err(self.error("unexpected end")),
+        };
+
+        Ok(Node::new(value, start..self.pos, line))
+    }
+
+    /// Reads the elements of an object or array up to `close`, allowing a trailing comma.
+    fn collection(
+        &mut self,
+        close: u8,
+        mut element: impl FnMut(&mut Self) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        self.pos += 1;
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(close) {
+                self.pos += 1;
+                return Ok(());
+            }
+
+            element(self)?;
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b) if b == close => {}
+                _ => return Err(self.error("expected ',' or the end of the collection")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> anyhow::Result<String> {
+        let quote = self.content[self.pos..].chars().next();
+        let mut chars = self.content[self.pos + 1..].char_indices();
+        let mut value = String::new();
+
+        while let Some((idx, c)) = chars.next() {
+            if Some(c) == quote {
+                self.pos += idx + 2;
+                return Ok(value);
+            }
+            if c != '\\' {
+                value.push(c);
+                continue;
+            }
+
+            match chars.next().map(|(_, c)| c) {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('r') => value.push('\r'),
+                Some('b') => value.push('\u{8}'),
+                Some('f') => value.push('\u{c}'),
+                Some('0') => value.push('\0'),
+                Some('u') => {
+                    let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                    let c = u32::from_str_radix(&hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .unwrap_or(char::REPLACEMENT_CHARACTER);
+                    value.push(c);
+                }
+                Some('\n') => {}
+                Some(c) => value.push(c),
+                None => break,
+            }
+        }
+
+        Err(self.error("unterminated string"))
+    }
+
+    /// Reads an unquoted key, number or literal like `true`.
+    fn bare(&mut self) -> anyhow::Result<String> {
+        let rest = &self.content[self.pos..];
+        let len = rest
+            .find(|c: char| c.is_whitespace() || ",:[]{}/\"'".contains(c))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("unexpected character"));
+        }
+
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    fn comments(&self) -> Vec<Comment> {
+        self.comments
+            .iter()
+            .map(|span| {
+                let raw = &self.content[span.clone()];
+                let line = self.lines.line(span.start);
+                let text = match raw.strip_prefix("//") {
+                    Some(text) => vec![text.trim().to_string()],
+                    None => {
+                        let lines: Vec<_> = raw[2..raw.len() - 2]
+                            .lines()
+                            .map(|l| l.trim().trim_start_matches('*').trim().to_string())
+                            .collect();
+                        let first = lines.iter().position(|l| !l.is_empty()).unwrap_or(0);
+                        let last = lines.iter().rposition(|l| !l.is_empty()).unwrap_or(0);
+                        lines[first..=last].to_vec()
+                    }
+                };
+
+                Comment {
+                    line,
+                    end_line: self.lines.line(span.end - 1),
+                    text,
+                    trailing: !self.content[self.lines.start(line)..span.start]
+                        .trim()
+                        .is_empty(),
+                }
+            })
+            .collect()
+    }
+}
+
+fn yaml_item(item: &str, indent: &str) -> String {
+    item.lines()
+        .enumerate()
+        .map(|(idx, line)| match (idx, line) {
+            (0, line) => format!("{}- {}\n", indent, line),
+            (_, "") => "\n".to_string(),
+            (_, line) => format!("{}  {}\n", indent, line),
+        })
+        .collect()
+}
+
+/// Inserts `text` at the start of the line following `pos`.
+fn insert_after_line(content: &mut String, pos: usize, text: &str) {
+    let mut pos = next_line(content, pos);
+    if !content[..pos].ends_with('\n') {
+        content.insert(pos, '\n');
+        pos += 1;
+    }
+    content.insert_str(pos, text);
+}
+
+fn insert_yaml_item<T: Serialize>(
+    content: &str,
+    root: &Node,
+    key: &str,
+    item: &T,
+) -> anyhow::Result<String> {
+    let yaml = serde_yaml::to_string(item)?;
+    let mut content = content.to_string();
+
+    let Some(entry) = root.entry(key) else {
+        content.truncate(content.trim_end().len());
+        if !content.is_empty() {
+            content.push('\n');
+        }
+        content += &format!("{}:\n{}", key, yaml_item(&yaml, "  "));
+        return Ok(content);
+    };
+
+    let seq = &entry.value;
+    match &seq.value {
+        Value::Seq(_) if !seq.flow => {
+            let indent = " ".repeat(seq.span.start - Lines::new(&content).start(seq.line));
+            insert_after_line(
+                &mut content,
+                seq.last_leaf().span.end,
+                &yaml_item(&yaml, &indent),
+            );
+        }
+        Value::Seq(items) if !items.is_empty() => {
+            let last = items[items.len() - 1].span.end;
+            content.insert_str(last, &format!(", {}", serde_json::to_string(item)?));
+        }
+        _ if seq.is_null(&content) || seq.items().is_some() => {
+            // `tasks:`, `tasks: ~` and `tasks: []` become a block sequence
+            if !seq.span.is_empty() {
+                let start = content[..seq.span.start]
+                    .trim_end_matches([' ', '\t'])
+                    .len();
+                content.replace_range(start..seq.span.end, "");
+            }
+
+            let key_start = Lines::new(&content).start(entry.line);
+            let indent = format!("{}  ", indentation(&content, key_start));
+            insert_after_line(&mut content, key_start, &yaml_item(&yaml, &indent));
+        }
+        _ => return Err(anyhow!("'{}' must be a sequence", key)),
+    }
+
+    Ok(content)
+}
+
+/// The indentation used by the document, four spaces if nothing is indented.
+fn indent_unit(content: &str) -> &str {
+    content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with(['*', '/']))
+        .map(|line| &line[..line.len() - line.trim_start_matches([' ', '\t']).len()])
+        .filter(|indent| !indent.is_empty())
+        .min_by_key(|indent| indent.len())
+        .unwrap_or("    ")
+}
+
+/// Indents the output of `serde_json::to_string_pretty` with `unit` below `base`.
+fn reindent(pretty: &str, base: &str, unit: &str) -> String {
+    pretty
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let depth = (line.len() - trimmed.len()) / 2;
+            format!("{}{}{}", base, unit.repeat(depth), trimmed)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn insert_json_item<T: Serialize>(
+    content: &str,
+    root: &Node,
+    key: &str,
+    item: &T,
+) -> anyhow::Result<String> {
+    let unit = indent_unit(content);
+
+    let Some(entry) = root.entry(key) else {
+        if !matches!(root.value, Value::Map(_)) {
+            return Err(anyhow!("the config must be an object"));
+        }
+        let key = serde_json::to_string(key)?;
+        let pretty = format!("{}: {}", key, serde_json::to_string_pretty(&[item])?);
+        let compact = format!("{}: [{}]", key, serde_json::to_string(item)?);
+        return Ok(append_element(content, root, &pretty, &compact, unit));
+    };
+
+    let seq = &entry.value;
+    if seq.is_null(content) {
+        let mut content = content.to_string();
+        content.replace_range(seq.span.clone(), "[]");
+        let root = JsonScanner::parse(&content)?.0;
+        return insert_json_item(&content, &root, key, item);
+    }
+    if seq.items().is_none() {
+        return Err(anyhow!("'{}' must be an array", key));
+    }
+
+    let pretty = serde_json::to_string_pretty(item)?;
+    let compact = serde_json::to_string(item)?;
+    Ok(append_element(content, seq, &pretty, &compact, unit))
+}
+
+/// Adds an element after the last one of the object or array `container`, following
+/// its layout and use of trailing commas.
+fn append_element(
+    content: &str,
+    container: &Node,
+    pretty: &str,
+    compact: &str,
+    unit: &str,
+) -> String {
+    let lines = Lines::new(content);
+    let (open, close) = (container.span.start, container.span.end - 1);
+    let mut content = content.to_string();
+
+    let last = match &container.value {
+        Value::Seq(items) => items.last().map(|node| (node.line, node.span.end)),
+        Value::Map(entries) => entries
+            .last()
+            .map(|entry| (entry.line, entry.value.span.end)),
+        Value::Scalar(_) => None,
+    };
+
+    let Some((last_line, last_end)) = last else {
+        let outer = indentation(&content, open).to_string();
+        let mut text = format!(
+            "\n{}",
+            reindent(pretty, &format!("{}{}", outer, unit), unit)
+        );
+        if lines.line(open) == lines.line(close) {
+            text += &format!("\n{}", outer);
+        }
+        content.insert_str(open + 1, &text);
+        return content;
+    };
+
+    if lines.line(last_end) == lines.line(close) {
+        content.insert_str(last_end, &format!(", {}", compact));
+        return content;
+    }
+
+    let mut scanner = JsonScanner {
+        content: &content,
+        lines: Lines::new(&content),
+        pos: last_end,
+        comments: vec![],
+    };
+    let comma = scanner
+        .skip_trivia()
+        .ok()
+        .and_then(|_| (scanner.peek() == Some(b',')).then_some(scanner.pos));
+
+    let indent = indentation(&content, lines.start(last_line)).to_string();
+    let pos = next_line(&content, comma.unwrap_or(last_end));
+    let mut text = reindent(pretty, &indent, unit);
+    if comma.is_some() {
+        text.push(',');
+    }
+
+    match content[..pos].strip_suffix('\n') {
+        Some(_) => content.insert_str(pos, &format!("{}\n", text)),
+        None => content.insert_str(pos, &format!("\n{}", text)),
+    }
+    if comma.is_none() {
+        content.insert(last_end, ',');
+    }
+
+    content
+}
+
+/// Position of the comma following `pos`, skipping whitespace and comments.
+fn comma_after(content: &str, pos: usize) -> Option<usize> {
+    let mut scanner = JsonScanner {
+        content,
+        lines: Lines::new(content),
+        pos,
+        comments: vec![],
+    };
+
+    scanner
+        .skip_trivia()
+        .ok()
+        .and_then(|_| (scanner.peek() == Some(b',')).then_some(scanner.pos))
+}
+
+/// Start of `line`, moved up over the comment lines directly above it.
+fn with_leading_comments(lines: &Lines, mut line: usize, leading: &HashMap<usize, usize>) -> usize {
+    while let Some(&first) = leading.get(&(line - 1)) {
+        line = first;
+    }
+
+    lines.start(line)
+}
+
+fn remove_yaml_item(
+    content: &str,
+    entry: &Entry,
+    idx: usize,
+    leading: &HashMap<usize, usize>,
+) -> String {
+    let lines = Lines::new(content);
+    let items = entry.value.items().unwrap_or_default();
+    let item = &items[idx];
+
+    let dash = content[..item.span.start]
+        .trim_end()
+        .strip_suffix('-')
+        .map_or(item.span.start, str::len);
+    let start = with_leading_comments(&lines, lines.line(dash), leading);
+    let end = next_line(content, item.last_leaf().span.end);
+
+    let mut content = content.to_string();
+    content.replace_range(start..end, "");
+
+    // an empty `tasks:` would be null
+    if items.len() == 1 {
+        let key_start = lines.start(entry.line);
+        if let Some(colon) = content[key_start..start].find(':') {
+            content.insert_str(key_start + colon + 1, " []");
+        }
+    }
+
+    content
+}
+
+/// Removes element `idx` of a json array or yaml flow sequence with its comma. An
+/// element on its own lines is removed with those lines, including the comments above it.
+fn remove_element(
+    content: &str,
+    items: &[Node],
+    idx: usize,
+    leading: &HashMap<usize, usize>,
+) -> String {
+    let lines = Lines::new(content);
+    let item = &items[idx];
+    let comma = comma_after(content, item.span.end);
+    let after = comma.map_or(item.span.end, |pos| pos + 1);
+
+    let rest = content[after..next_line(content, after)].trim();
+    let own_line = content[lines.start(item.line)..item.span.start]
+        .trim()
+        .is_empty()
+        && (rest.is_empty() || rest.starts_with(['/', '#']));
+
+    let mut content = content.to_string();
+    if !own_line {
+        let range = match (items.get(idx + 1), idx.checked_sub(1)) {
+            (Some(next), _) => item.span.start..next.span.start,
+            (None, Some(prev)) => items[prev].span.end..item.span.end,
+            (None, None) => item.span.start..after,
+        };
+        content.replace_range(range, "");
+        return content;
+    }
+
+    let start = with_leading_comments(&lines, item.line, leading);
+    content.replace_range(start..next_line(&content, after), "");
+
+    // the previous element is the last one now
+    if comma.is_none() && idx + 1 == items.len() && idx > 0 {
+        if let Some(pos) = comma_after(&content, items[idx - 1].span.end) {
+            content.remove(pos);
+        }
+    }
+
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::Input;
+    use crate::tasks::{TaskEntry, Tasks};
+
+    fn create_test_task(label: &str, command: &str) -> TaskEntry {
+        TaskEntry {
+            label: label.to_string(),
+            command: command.to_string(),
+            id: Some("new".to_string()),
+            conditions: None,
+            line: None,
+        }
+    }
+
+    fn create_test_input() -> Input {
+        Input {
+            id: "env".to_string(),
+            options: vec!["dev".into()],
+            ..Default::default()
+        }
+    }
+
+    fn push<T: Serialize>(content: &str, format: TaskFormat, key: &str, item: &T) -> String {
+        let mut document = ConfigDocument::new(content, format);
+        document.push_item(key, item).unwrap();
+        document.content().to_string()
+    }
+
+    fn insert_task(content: &str, format: TaskFormat) -> String {
+        push(
+            content,
+            format,
+            "tasks",
+            &create_test_task("New task", "echo new"),
+        )
+    }
+
+    #[test]
+    fn test_insert_yaml_task_keeps_comments() {
+        let content = r#"# my tasks
+version: "1.0.0"
+tasks:
+  # first task
+  - label: "First"
+    command: "echo first" # inline comment
+    description: "unknown field"
+
+# inputs below
+inputs:
+  - id: env
+    options: [dev]
+"#;
+
+        let expected = r#"# my tasks
+version: "1.0.0"
+tasks:
+  # first task
+  - label: "First"
+    command: "echo first" # inline comment
+    description: "unknown field"
+  - label: New task
+    command: echo new
+    id: new
+
+# inputs below
+inputs:
+  - id: env
+    options: [dev]
+"#;
+
+        assert_eq!(insert_task(content, TaskFormat::Yaml), expected);
+    }
+
+    #[test]
+    fn test_insert_yaml_task_after_block_scalar() {
+        let content =
+            "tasks:\n- label: First\n  command: |\n    # not a comment\n\n    echo first\n# end\n";
+        let expected = "tasks:\n- label: First\n  command: |\n    # not a comment\n\n    echo first\n- label: New task\n  command: echo new\n  id: new\n# end\n";
+
+        assert_eq!(insert_task(content, TaskFormat::Yaml), expected);
+    }
+
+    #[test]
+    fn test_insert_yaml_new_sequence() {
+        let content = "tasks:\n  - label: First\n    command: echo first\n\n";
+        let result = push(content, TaskFormat::Yaml, "inputs", &create_test_input());
+
+        assert_eq!(
+            result,
+            "tasks:\n  - label: First\n    command: echo first\ninputs:\n  - id: env\n    options:\n    - dev\n"
+        );
+        assert!(serde_yaml::from_str::<Tasks>(&result).is_ok());
+    }
+
+    #[test]
+    fn test_insert_yaml_empty_sequence() {
+        let expected = "version: \"1.0.0\"\ntasks:\n  - label: New task\n    command: echo new\n    id: new\ninputs: []\n";
+
+        for content in [
+            "version: \"1.0.0\"\ntasks:\ninputs: []\n",
+            "version: \"1.0.0\"\ntasks: ~\ninputs: []\n",
+            "version: \"1.0.0\"\ntasks: []\ninputs: []\n",
+        ] {
+            assert_eq!(insert_task(content, TaskFormat::Yaml), expected);
+        }
+    }
+
+    #[test]
+    fn test_insert_yaml_flow_sequence() {
+        let content = "tasks: [{label: First, command: echo first}] # all\n";
+        let result = insert_task(content, TaskFormat::Yaml);
+        let tasks: Tasks = serde_yaml::from_str(&result).unwrap();
+
+        assert_eq!(tasks.tasks.len(), 2);
+        assert!(result.ends_with("}] # all\n"));
+    }
+
+    #[test]
+    fn test_insert_json_task_keeps_comments() {
+        let content = r#"{
+    // vscode tasks
+    "version": "2.0.0",
+    "tasks": [
+        {
+            "label": "First", /* inline */
+            "command": "echo ']' first",
+        }, // first
+    ],
+}
+"#;
+
+        let expected = r#"{
+    // vscode tasks
+    "version": "2.0.0",
+    "tasks": [
+        {
+            "label": "First", /* inline */
+            "command": "echo ']' first",
+        }, // first
+        {
+            "label": "New task",
+            "command": "echo new",
+            "id": "new"
+        },
+    ],
+}
+"#;
+
+        assert_eq!(insert_task(content, TaskFormat::Json), expected);
+    }
+
+    #[test]
+    fn test_insert_json_task_without_trailing_comma() {
+        let content = "{\n  \"tasks\": [\n    { \"label\": \"First\", \"command\": \"echo first\" } // first\n  ]\n}\n";
+        let expected = "{\n  \"tasks\": [\n    { \"label\": \"First\", \"command\": \"echo first\" }, // first\n    {\n      \"label\": \"New task\",\n      \"command\": \"echo new\",\n      \"id\": \"new\"\n    }\n  ]\n}\n";
+
+        assert_eq!(insert_task(content, TaskFormat::Json), expected);
+    }
+
+    #[test]
+    fn test_insert_json_inline_array() {
+        let content = r#"{"tasks": [{"label": "a", "command": "a"}]}"#;
+        let result = insert_task(content, TaskFormat::Json);
+
+        assert_eq!(
+            result,
+            r#"{"tasks": [{"label": "a", "command": "a"}, {"label":"New task","command":"echo new","id":"new"}]}"#
+        );
+    }
+
+    #[test]
+    fn test_insert_json_empty_array() {
+        let content = "{\n    \"version\": \"1.0.0\",\n    \"tasks\": []\n}\n";
+        let expected = "{\n    \"version\": \"1.0.0\",\n    \"tasks\": [\n        {\n            \"label\": \"New task\",\n            \"command\": \"echo new\",\n            \"id\": \"new\"\n        }\n    ]\n}\n";
+
+        assert_eq!(insert_task(content, TaskFormat::Json), expected);
+        assert_eq!(
+            insert_task(&content.replace("[]", "null"), TaskFormat::Json),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_insert_json_new_member() {
+        let content = "{\n\ttasks: [],\n\tversion: '1.0.0' // version\n}\n";
+        let expected = "{\n\ttasks: [],\n\tversion: '1.0.0', // version\n\t\"inputs\": [\n\t\t{\n\t\t\t\"id\": \"env\",\n\t\t\t\"options\": [\n\t\t\t\t\"dev\"\n\t\t\t]\n\t\t}\n\t]\n}\n";
+        let result = push(content, TaskFormat::Json, "inputs", &create_test_input());
+
+        assert_eq!(result, expected);
+        assert!(serde_json5::from_str::<Tasks>(&result).is_ok());
+    }
+
+    fn remove_task(content: &str, format: TaskFormat, idx: usize) -> String {
+        let mut document = ConfigDocument::new(content, format);
+        document.remove_item("tasks", idx).unwrap();
+
+        let result = document.content().to_string();
+        match format {
+            TaskFormat::Yaml => assert!(serde_yaml::from_str::<Tasks>(&result).is_ok()),
+            TaskFormat::Json => assert!(serde_json5::from_str::<Tasks>(&result).is_ok()),
+        }
+
+        result
+    }
+
+    #[test]
+    fn test_remove_yaml_task_keeps_comments() {
+        let content = r#"# my tasks
+tasks:
+  # first task
+  - label: First
+    command: echo first # inline
+
+  # second task
+  - label: Second
+    command: |
+      # not a comment
+      echo second
+  # third task
+  -
+    label: Third
+    command: echo third
+# inputs below
+inputs: []
+"#;
+
+        let expected = [
+            r#"# my tasks
+tasks:
+
+  # second task
+  - label: Second
+    command: |
+      # not a comment
+      echo second
+  # third task
+  -
+    label: Third
+    command: echo third
+# inputs below
+inputs: []
+"#,
+            r#"# my tasks
+tasks:
+  # first task
+  - label: First
+    command: echo first # inline
+
+  # third task
+  -
+    label: Third
+    command: echo third
+# inputs below
+inputs: []
+"#,
+            r#"# my tasks
+tasks:
+  # first task
+  - label: First
+    command: echo first # inline
+
+  # second task
+  - label: Second
+    command: |
+      # not a comment
+      echo second
+# inputs below
+inputs: []
+"#,
+        ];
+
+        for (idx, expected) in expected.iter().enumerate() {
+            assert_eq!(remove_task(content, TaskFormat::Yaml, idx), *expected);
+        }
+    }
+
+    #[test]
+    fn test_remove_yaml_only_task() {
+        let content = "tasks: # all\n  # only task\n  - label: First\n    command: echo first\n";
+
+        assert_eq!(
+            remove_task(content, TaskFormat::Yaml, 0),
+            "tasks: [] # all\n"
+        );
+    }
+
+    #[test]
+    fn test_remove_json_task_keeps_comments() {
+        let content = r#"{
+    // vscode tasks
+    "tasks": [
+        // first task
+        {
+            "label": "First",
+            "command": "echo first",
+        }, // first
+        /* second
+           task */
+        {
+            "label": "Second",
+            "command": "echo second"
+        },
+        // third task
+        {
+            "label": "Third",
+            "command": "echo third" // third
+        }
+    ],
+}
+"#;
+
+        let expected = [
+            r#"{
+    // vscode tasks
+    "tasks": [
+        /* second
+           task */
+        {
+            "label": "Second",
+            "command": "echo second"
+        },
+        // third task
+        {
+            "label": "Third",
+            "command": "echo third" // third
+        }
+    ],
+}
+"#,
+            r#"{
+    // vscode tasks
+    "tasks": [
+        // first task
+        {
+            "label": "First",
+            "command": "echo first",
+        }, // first
+        // third task
+        {
+            "label": "Third",
+            "command": "echo third" // third
+        }
+    ],
+}
+"#,
+            r#"{
+    // vscode tasks
+    "tasks": [
+        // first task
+        {
+            "label": "First",
+            "command": "echo first",
+        }, // first
+        /* second
+           task */
+        {
+            "label": "Second",
+            "command": "echo second"
+        }
+    ],
+}
+"#,
+        ];
+
+        for (idx, expected) in expected.iter().enumerate() {
+            assert_eq!(remove_task(content, TaskFormat::Json, idx), *expected);
+        }
+    }
+
+    #[test]
+    fn test_remove_json_inline_task() {
+        let content = r#"{"tasks": [{"label": "a", "command": "a"}, {"label": "b", "command": "b"}, {"label": "c", "command": "c"}]}"#;
+
+        assert_eq!(
+            remove_task(content, TaskFormat::Json, 0),
+            r#"{"tasks": [{"label": "b", "command": "b"}, {"label": "c", "command": "c"}]}"#
+        );
+        assert_eq!(
+            remove_task(content, TaskFormat::Json, 1),
+            r#"{"tasks": [{"label": "a", "command": "a"}, {"label": "c", "command": "c"}]}"#
+        );
+        assert_eq!(
+            remove_task(content, TaskFormat::Json, 2),
+            r#"{"tasks": [{"label": "a", "command": "a"}, {"label": "b", "command": "b"}]}"#
+        );
+    }
+
+    #[test]
+    fn test_root_lines() {
+        let content = "tasks:\n  - label: 'It''s quoted' # a\n    command: |\n      echo\n\n  -\n    command: echo\n    label: b\n";
+        let root = ConfigDocument::new(content, TaskFormat::Yaml)
+            .root()
+            .unwrap();
+        let tasks = root.get("tasks").and_then(Node::items).unwrap();
+
+        assert_eq!(
+            tasks[0].get("label").and_then(Node::as_str),
+            Some("It's quoted")
+        );
+        assert_eq!(
+            tasks[0].get("command").and_then(Node::as_str),
+            Some("echo\n")
+        );
+        assert_eq!(
+            tasks.iter().map(|task| task.line).collect::<Vec<_>>(),
+            [2, 7]
+        );
+
+        let content = "// tasks\n{\n  tasks: [\n    {label: \"a\\\"b\", command: 'echo'},\n  ],\n}";
+        let root = ConfigDocument::new(content, TaskFormat::Json)
+            .root()
+            .unwrap();
+        let tasks = root.get("tasks").and_then(Node::items).unwrap();
+
+        assert_eq!(tasks[0].get("label").and_then(Node::as_str), Some("a\"b"));
+        assert_eq!(tasks[0].line, 4);
+    }
+
+    fn convert(content: &str, from: TaskFormat, to: TaskFormat) -> String {
+        let tasks: Tasks = match from {
+            TaskFormat::Yaml => serde_yaml::from_str(content).unwrap(),
+            TaskFormat::Json => serde_json5::from_str(content).unwrap(),
+        };
+        ConfigDocument::new(content, from)
+            .convert(to, &tasks)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_yaml_to_json_keeps_comments() {
+        let content = r#"# my tasks
+version: "1.0.0"
+
+tasks:
+  # first task
+  - label: First # inline
+    command: |
+      # not a comment
+      echo first
+    description: unknown field
+inputs:
+- id: env
+  default: 2
+  options: [1, '2']
+# the end
+"#;
+
+        let expected = r##"{
+  // my tasks
+  "version": "1.0.0",
+
+  "tasks": [
+    // first task
+    {
+      "label": "First", // inline
+      "command": "# not a comment\necho first\n",
+      "description": "unknown field"
+    }
+  ],
+  "inputs": [
+    {
+      "id": "env",
+      "default": "2",
+      "options": [
+        "1",
+        "2"
+      ]
+    }
+  ]
+}
+// the end
+"##;
+
+        assert_eq!(
+            convert(content, TaskFormat::Yaml, TaskFormat::Json),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_json5_to_yaml_keeps_comments() {
+        let content = r#"// my tasks
+{
+    version: '1.0.0',
+    /* all tasks
+     * of the project */
+    tasks: [
+        {
+            label: "First", // inline
+            command: 'echo "first" // not a comment',
+            timeout: 10,
+        },
+        { "label": "Multi", "command": "echo a\nb\n" },
+    ],
+}
+"#;
+
+        let expected = r#"# my tasks
+version: 1.0.0
+# all tasks
+# of the project
+tasks:
+- label: First # inline
+  command: echo "first" // not a comment
+  timeout: 10
+- label: Multi
+  command: |
+    echo a
+    b
+"#;
+
+        assert_eq!(
+            convert(content, TaskFormat::Json, TaskFormat::Yaml),
+            expected
+        );
+    }
+}
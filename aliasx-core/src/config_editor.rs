@@ -0,0 +1,303 @@
+use anyhow::{anyhow, Context};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    config_document::{ConfigDocument, Node},
+    input::Input,
+    input_mapping::InputMapping,
+    task_filter::TaskFilter,
+    task_reader::{self, TaskFormat},
+    tasks::{TaskEntry, Tasks, GLOBAL_SOURCES, LOCAL_SOURCES},
+    validator::{ValidationReport, Validator},
+};
+
+const NEW_CONFIG: &str = "version: \"1.0.0\"\ntasks:\n";
+const NEW_JSON_CONFIG: &str = "{\n    \"version\": \"1.0.0\",\n    \"tasks\": []\n}\n";
+
+/// Edits config files in place.
+///
+/// Changes go through [`ConfigDocument`] so existing comments, formatting and fields
+/// unknown to aliasx are left untouched.
+pub struct ConfigEditor;
+
+impl ConfigEditor {
+    /// Returns the config file new tasks should be added to for `scope`.
+    /// The first existing aliasx config is used - otherwise a new `.aliasx.yaml`.
+    pub fn target_path(scope: TaskFilter) -> anyhow::Result<PathBuf> {
+        let (dir, sources) = match scope {
+            TaskFilter::Local => (PathBuf::new(), LOCAL_SOURCES),
+            TaskFilter::Global => (
+                dirs::home_dir().context("could not find global configs")?,
+                GLOBAL_SOURCES,
+            ),
+            _ => {
+                return Err(anyhow!(
+                    "tasks can only be added to local or global configs"
+                ))
+            }
+        };
+
+        Ok(Self::config_in(&dir, sources))
+    }
+
+    /// `.vscode/tasks.json` is read but never written, it belongs to vscode.
+    fn config_in(dir: &Path, sources: &[&str]) -> PathBuf {
+        let sources: Vec<_> = sources
+            .iter()
+            .filter(|source| !source.starts_with(".vscode"))
+            .collect();
+
+        sources
+            .iter()
+            .map(|source| dir.join(source))
+            .find(|path| path.is_file())
+            .unwrap_or_else(|| dir.join(sources[0]))
+    }
+
+    /// Appends `task`, `inputs` and `mappings` to the config at `path` (created if missing)
+    /// and validates the new task. The file is restored if the result can't be parsed.
+    pub fn append_task<P: AsRef<Path>>(
+        path: P,
+        task: &TaskEntry,
+        inputs: &[Input],
+        mappings: &[InputMapping],
+    ) -> anyhow::Result<ValidationReport> {
+        let path = path.as_ref();
+        let format = TaskFormat::from_path(path)
+            .with_context(|| format!("unsupported file format: {:?}", path))?;
+
+        let mut document = if path.is_file() {
+            ConfigDocument::load(path)?
+        } else {
+            let content = match format {
+                TaskFormat::Yaml => NEW_CONFIG,
+                TaskFormat::Json => NEW_JSON_CONFIG,
+            };
+            ConfigDocument::new(content, format)
+        };
+
+        document.push_item("tasks", task)?;
+        for input in inputs {
+            document.push_item("inputs", input)?;
+        }
+        for mapping in mappings {
+            document.push_item("mappings", mapping)?;
+        }
+
+        let tasks = Self::write_checked(path, &document)?;
+        Ok(Self::validate_task(&tasks, task))
+    }
+
+    /// Removes the first definition of `task` from the config at `path`.
+    pub fn remove_task<P: AsRef<Path>>(path: P, task: &TaskEntry) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        if path.ends_with(".vscode/tasks.json") {
+            return Err(anyhow!("{:?} belongs to vscode and isn't edited", path));
+        }
+        let mut document = ConfigDocument::load(path)?;
+
+        let idx = document
+            .root()?
+            .get("tasks")
+            .and_then(Node::items)
+            .and_then(|items| {
+                items.iter().position(|item| {
+                    item.get("label").and_then(Node::as_str) == Some(task.label.as_str())
+                        && item.get("command").and_then(Node::as_str) == Some(task.command.as_str())
+                })
+            })
+            .ok_or_else(|| anyhow!("couldn't find task '{}' in {:?}", task.label, path))?;
+
+        document.remove_item("tasks", idx)?;
+        Self::write_checked(path, &document)?;
+
+        Ok(())
+    }
+
+    /// Writes `document` to `path` and parses it again.
+    /// The previous content is restored if the result is invalid.
+    fn write_checked(path: &Path, document: &ConfigDocument) -> anyhow::Result<Tasks> {
+        let original = if path.is_file() {
+            Some(std::fs::read_to_string(path)?)
+        } else {
+            None
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, document.content())?;
+
+        match task_reader::parse_file(path) {
+            Ok(tasks) => Ok(tasks),
+            Err(err) => {
+                match original {
+                    Some(original) => std::fs::write(path, original)?,
+                    None => std::fs::remove_file(path)?,
+                }
+                Err(err.context("the edited config is invalid - no changes were made"))
+            }
+        }
+    }
+
+    fn validate_task(tasks: &Tasks, task: &TaskEntry) -> ValidationReport {
+        let validator = Validator {
+            verbose: true,
+            run_commands: false,
+        };
+        validator.validate_task_command(task, tasks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_task(label: &str, command: &str) -> TaskEntry {
+        TaskEntry {
+            label: label.to_string(),
+            command: command.to_string(),
+            id: None,
+            conditions: None,
+            line: None,
+        }
+    }
+
+    #[test]
+    fn test_config_in_skips_vscode_tasks() {
+        let dir = std::env::temp_dir().join(format!("aliasx-editor-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join(".vscode")).unwrap();
+        std::fs::write(dir.join(".vscode/tasks.json"), "{}").unwrap();
+
+        let path = ConfigEditor::config_in(&dir, LOCAL_SOURCES);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(path, dir.join(".aliasx.yaml"));
+    }
+
+    #[test]
+    fn test_append_task() {
+        let path = std::env::temp_dir().join(format!("aliasx-editor-{}.yaml", std::process::id()));
+        let content = "# keep me\nversion: \"1.0.0\"\ntasks:\n  - label: First # first\n    command: echo first\n";
+        std::fs::write(&path, content).unwrap();
+
+        ConfigEditor::append_task(&path, &create_test_task("Second", "echo second"), &[], &[])
+            .unwrap();
+        let result = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            result,
+            format!("{}  - label: Second\n    command: echo second\n", content)
+        );
+    }
+
+    #[test]
+    fn test_append_and_remove_task() {
+        let path = std::env::temp_dir().join(format!("aliasx-remove-{}.yaml", std::process::id()));
+        let content = "# keep me\nversion: \"1.0.0\"\ntasks:\n  - label: First # first\n    command: echo first\n";
+        std::fs::write(&path, content).unwrap();
+
+        let task = create_test_task("Second", "echo second");
+        ConfigEditor::append_task(&path, &task, &[], &[]).unwrap();
+        assert_eq!(task_reader::parse_file(&path).unwrap().tasks.len(), 2);
+
+        ConfigEditor::remove_task(&path, &task).unwrap();
+        let result = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(result, content);
+    }
+
+    #[test]
+    fn test_remove_unknown_task() {
+        let path = std::env::temp_dir().join(format!("aliasx-remove-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            "{\"tasks\": [{\"label\": \"a\", \"command\": \"a\"}]}",
+        )
+        .unwrap();
+
+        let result = ConfigEditor::remove_task(&path, &create_test_task("b", "b"));
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_append_promoted_task() {
+        let source: Tasks = serde_yaml::from_str(
+            r#"
+tasks:
+  - label: Deploy
+    command: deploy ${input:region} --host ${mapping:host.name}
+inputs:
+  - id: env
+    options: [dev, prod]
+  - id: region
+    options_by:
+      input: env
+      values:
+        dev: [local]
+        prod: [eu]
+  - id: unused
+mappings:
+  - id: host
+    inputs: [env, region]
+    options:
+      dev/local: {name: localhost}
+      prod/eu: {name: eu.example.com}
+"#,
+        )
+        .unwrap();
+
+        let path = std::env::temp_dir().join(format!("aliasx-promote-{}.json", std::process::id()));
+        let content =
+            "{\n  // other project\n  \"tasks\": [{\"label\": \"a\", \"command\": \"a\"}]\n}\n";
+        std::fs::write(&path, content).unwrap();
+
+        let target = task_reader::parse_file(&path).unwrap();
+        let command = "deploy ${input:region} --host ${mapping:host.name}";
+        let (inputs, mappings) = source.definitions_missing_in(command, &target).unwrap();
+        let ids: Vec<&str> = inputs.iter().map(|input| input.id.as_str()).collect();
+        assert_eq!(ids, vec!["env", "region"]);
+        assert_eq!(mappings.len(), 1);
+
+        let task = create_test_task("Deploy", command);
+        let report = ConfigEditor::append_task(&path, &task, &inputs, &mappings).unwrap();
+        let result = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(!report.has_failures());
+        assert!(result.contains("// other project"));
+
+        // nothing is missing once the target defines everything
+        let target: Tasks = serde_json5::from_str(&result).unwrap();
+        assert_eq!(
+            source.definitions_missing_in(command, &target).unwrap(),
+            (vec![], vec![])
+        );
+
+        let err = Tasks::default()
+            .definitions_missing_in(command, &Tasks::default())
+            .unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "mapping 'host.name' can't be copied: no mappings defined"
+        );
+    }
+
+    #[test]
+    fn test_append_invalid_task() {
+        let path = std::env::temp_dir().join(format!("aliasx-editor-{}.json", std::process::id()));
+        let content = "{\"tasks\": [{\"label\": \"a\", \"command\": \"a\"}], \"inputs\": 1}";
+        std::fs::write(&path, content).unwrap();
+
+        let result = ConfigEditor::append_task(&path, &create_test_task("b", "b"), &[], &[]);
+        let after = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(after, content);
+    }
+}
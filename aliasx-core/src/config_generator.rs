@@ -1,25 +1,120 @@
 use crate::{
-    input::Input,
+    config_document::ConfigDocument,
+    input::{Input, InputOption},
     input_mapping::InputMapping,
-    task_reader::TaskFormat,
+    task_reader::{self, TaskFormat},
     tasks::{TaskEntry, Tasks},
 };
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
+use schemars::{schema_for, Schema};
+use serde::Serialize;
 use std::path::Path;
 
+/// The schema printed by `config-generator schema`, published with the repository.
+const SCHEMA_URL: &str =
+    "https://raw.githubusercontent.com/hansbinderup/aliasx/main/aliasx.schema.json";
+
 pub struct ConfigGenerator;
 
+#[derive(Debug, Serialize)]
+struct VsCodeTasks {
+    version: String,
+    tasks: Vec<VsCodeTask>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    inputs: Vec<VsCodeInput>,
+}
+
+#[derive(Debug, Serialize)]
+struct VsCodeTask {
+    label: String,
+
+    #[serde(rename = "type")]
+    task_type: String,
+
+    command: String,
+}
+
+#[derive(Debug, Serialize)]
+struct VsCodeInput {
+    id: String,
+
+    #[serde(rename = "type")]
+    input_type: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    options: Vec<VsCodeOption>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<String>,
+
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    password: bool,
+}
+
+/// pickString options are strings or `{ label, value }` objects
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+enum VsCodeOption {
+    Value(String),
+    Labelled { label: String, value: String },
+}
+
+impl From<&InputOption> for VsCodeOption {
+    fn from(option: &InputOption) -> Self {
+        match &option.label {
+            Some(label) => Self::Labelled {
+                label: label.clone(),
+                value: option.value.clone(),
+            },
+            None => Self::Value(option.value.clone()),
+        }
+    }
+}
+
+impl VsCodeTask {
+    fn shell(label: String, command: String) -> Self {
+        Self {
+            label,
+            task_type: "shell".to_string(),
+            command,
+        }
+    }
+}
+
+impl From<&Input> for VsCodeInput {
+    fn from(input: &Input) -> Self {
+        Self {
+            id: input.id.clone(),
+            input_type: if input.options.is_empty() {
+                "promptString".to_string()
+            } else {
+                "pickString".to_string()
+            },
+            description: input.description.clone(),
+            options: input.options.iter().map(VsCodeOption::from).collect(),
+            default: input.default.clone(),
+            password: input.is_secret() && input.options.is_empty(),
+        }
+    }
+}
+
 impl ConfigGenerator {
     fn create_example_config() -> Tasks {
-        let mut tasks = Tasks::default();
-
-        tasks.version = Some("1.0.0".to_string());
+        let mut tasks = Tasks {
+            version: Some("1.0.0".to_string()),
+            ..Default::default()
+        };
 
         tasks.tasks.insert(TaskEntry {
             id: None,
             label: "Simple task".to_string(),
             command: "echo 'This is just a simple task'".to_string(),
             conditions: None,
+            line: None,
         });
 
         tasks.tasks.insert(TaskEntry {
@@ -27,66 +122,505 @@ impl ConfigGenerator {
             label: "Perform build".to_string(),
             command: "echo 'building ${input:build-type} in ${mapping:build-dir}...'".to_string(),
             conditions: None,
+            line: None,
         });
 
         tasks.inputs.push(Input {
             id: "build-type".to_string(),
-            options: vec![
-                "release".to_string(),
-                "debug".to_string(),
-                "test".to_string(),
-            ],
-            default: None,
+            options: vec!["release".into(), "debug".into(), "test".into()],
             description: Some("Pick a build type".to_string()),
+            ..Default::default()
         });
 
         tasks.mappings.push(InputMapping {
             id: "build-dir".to_string(),
-            input: "build-type".to_string(),
+            input: Some("build-type".to_string()),
+            inputs: Vec::new(),
             options: IndexMap::from([
-                ("release".to_string(), ".build-release".to_string()),
-                ("debug".to_string(), ".build-debug".to_string()),
-                ("test".to_string(), ".build-test".to_string()),
+                ("release".to_string(), ".build-release".into()),
+                ("debug".to_string(), ".build-debug".into()),
+                ("test".to_string(), ".build-test".into()),
             ]),
+            default: None,
+            passthrough: false,
         });
 
         tasks
     }
 
-    pub fn print_example_config(format: TaskFormat) -> anyhow::Result<()> {
+    fn example_config(format: TaskFormat) -> anyhow::Result<String> {
         let config = ConfigGenerator::create_example_config();
 
-        let config_str = match format {
-            TaskFormat::Yaml => serde_yaml::to_string(&config)?,
+        Ok(match format {
+            TaskFormat::Yaml => format!(
+                "# yaml-language-server: $schema={}\n{}",
+                SCHEMA_URL,
+                serde_yaml::to_string(&config)?
+            ),
             TaskFormat::Json => serde_json::to_string_pretty(&config)?,
+        })
+    }
+
+    pub fn print_example_config(format: TaskFormat) -> anyhow::Result<()> {
+        print!("{}", Self::example_config(format)?);
+
+        Ok(())
+    }
+
+    /// JSON Schema of the config files, used by editors for completion and validation.
+    fn schema() -> Schema {
+        schema_for!(Tasks)
+    }
+
+    pub fn print_schema() -> anyhow::Result<()> {
+        println!("{}", serde_json::to_string_pretty(&Self::schema())?);
+
+        Ok(())
+    }
+
+    /// VS Code has no equivalent of mappings, so tasks using them are expanded into
+    /// one task per combination of the inputs the mappings depend on.
+    fn expand_mappings(
+        entry: &TaskEntry,
+        tasks: &Tasks,
+        report: &mut Vec<String>,
+    ) -> Vec<VsCodeTask> {
+        if InputMapping::extract_from_str(&entry.command).is_empty() {
+            return vec![VsCodeTask::shell(
+                entry.label.clone(),
+                entry.command.clone(),
+            )];
+        }
+
+        let mut driver_ids: IndexSet<&str> = IndexSet::new();
+        for map_id in InputMapping::extract_from_str(&entry.command) {
+            match tasks.resolve_mapping(&map_id) {
+                Ok((mapping, _)) => {
+                    driver_ids.extend(mapping.input_ids());
+                }
+                Err(err) => {
+                    report.push(format!("task '{}' skipped: {}", entry.label, err));
+                    return vec![];
+                }
+            }
+        }
+
+        let drivers: Vec<&Input> = match driver_ids.iter().map(|id| tasks.get_input(id)).collect() {
+            Ok(drivers) => drivers,
+            Err(err) => {
+                report.push(format!("task '{}' skipped: {}", entry.label, err));
+                return vec![];
+            }
         };
 
-        print!("{}", config_str);
+        // dynamic options are only known when prompting
+        if let Some(input) = drivers.iter().find(|input| input.options.is_empty()) {
+            report.push(format!(
+                "task '{}' skipped: input '{}' has no static options",
+                entry.label, input.id
+            ));
+            return vec![];
+        }
+
+        // cartesian product of all driver options
+        let mut combinations: Vec<Vec<&str>> = vec![vec![]];
+        for input in drivers.iter() {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combination| {
+                    input.options.iter().map(move |option| {
+                        let mut next = combination.clone();
+                        next.push(option.value.as_str());
+                        next
+                    })
+                })
+                .collect();
+        }
+
+        let mut expanded = Vec::new();
+
+        for combination in combinations {
+            let mut command = entry.command.clone();
+            let mut selections = IndexMap::new();
+
+            for (input, value) in drivers.iter().zip(combination.iter()) {
+                command = command.replace(&format!("${{input:{}}}", input.id), value);
+                selections.insert(input.id.clone(), value.to_string());
+            }
+
+            let values = combination.join(", ");
+
+            match tasks.apply_mappings(&command, &selections) {
+                Ok(command) => expanded.push(VsCodeTask::shell(
+                    format!("{} ({})", entry.label, values),
+                    command,
+                )),
+                Err(err) => report.push(format!(
+                    "task '{}' skipped for '{}': {}",
+                    entry.label, values, err
+                )),
+            }
+        }
+
+        expanded
+    }
+
+    fn to_vscode(tasks: &Tasks) -> (VsCodeTasks, Vec<String>) {
+        let mut report = Vec::new();
+        let mut vscode_tasks = Vec::new();
+
+        for entry in tasks.tasks.iter() {
+            if entry.conditions.is_some() {
+                report.push(format!(
+                    "task '{}': conditions are not supported and were dropped",
+                    entry.label
+                ));
+            }
+
+            vscode_tasks.extend(Self::expand_mappings(entry, tasks, &mut report));
+        }
+
+        let mut input_ids: IndexSet<String> = IndexSet::new();
+        for task in vscode_tasks.iter() {
+            input_ids.extend(Input::extract_variables(&task.command));
+        }
+
+        let mut inputs = Vec::new();
+        for id in input_ids {
+            match tasks.get_input(&id) {
+                Ok(input) => {
+                    if input.options_command.is_some() || input.options_glob.is_some() {
+                        report.push(format!(
+                            "input '{}': options_command and options_glob are not supported, only static options were exported",
+                            input.id
+                        ));
+                    }
+                    if input.options_by.is_some() {
+                        report.push(format!(
+                            "input '{}': options_by is not supported, all of its options were exported",
+                            input.id
+                        ));
+                    }
+                    if input.options.iter().any(|o| o.description.is_some()) {
+                        report.push(format!(
+                            "input '{}': option descriptions are not supported",
+                            input.id
+                        ));
+                    }
+                    if input.default_env.is_some() || input.default_command.is_some() {
+                        report.push(format!(
+                            "input '{}': default_env and default_command are not supported, only the static default was exported",
+                            input.id
+                        ));
+                    }
+                    if input.secret_env.is_some() {
+                        report.push(format!(
+                            "input '{}': secret_env is not supported, the value is inlined",
+                            input.id
+                        ));
+                    }
+                    if input.multiple {
+                        report.push(format!(
+                            "input '{}': multiple is not supported, a single option is picked",
+                            input.id
+                        ));
+                    }
+                    if input.allow_custom && !input.options.is_empty() {
+                        report.push(format!(
+                            "input '{}': custom values are not supported by pickString",
+                            input.id
+                        ));
+                    }
+                    match input.select_options_by(&IndexMap::new()) {
+                        Ok(input) => inputs.push(VsCodeInput::from(&input)),
+                        Err(err) => report.push(err.to_string()),
+                    }
+                }
+                Err(err) => report.push(err.to_string()),
+            }
+        }
+
+        let config = VsCodeTasks {
+            version: "2.0.0".to_string(),
+            tasks: vscode_tasks,
+            inputs,
+        };
+
+        (config, report)
+    }
+
+    /// Prints the config as a VS Code `tasks.json`.
+    /// Anything that couldn't be translated is reported on stderr.
+    pub fn convert_to_vscode<P: AsRef<Path>>(path: P) -> anyhow::Result<()> {
+        let tasks = task_reader::parse_file(path)?;
+        let (config, report) = Self::to_vscode(&tasks);
+
+        println!("{}", serde_json::to_string_pretty(&config)?);
+
+        if !report.is_empty() {
+            eprintln!("Not translated:");
+            for line in report {
+                eprintln!("  - {}", line);
+            }
+        }
 
         Ok(())
     }
 
-    pub fn convert_json_to_yaml<P: AsRef<Path>>(path: P) -> anyhow::Result<()> {
-        let file = std::fs::File::open(path)?;
-        let reader = std::io::BufReader::new(file);
-        let tasks: Tasks = serde_json5::from_reader(reader)?;
+    /// Converts the config at `path` keeping comments, key order and unknown fields.
+    fn convert<P: AsRef<Path>>(
+        path: P,
+        from: TaskFormat,
+        to: TaskFormat,
+    ) -> anyhow::Result<String> {
+        let content = std::fs::read_to_string(path)?;
+        let tasks = Self::parse(&content, from)?;
 
-        let yaml_str = serde_yaml::to_string(&tasks)?;
+        ConfigDocument::new(content, from).convert(to, &tasks)
+    }
 
-        print!("{}", yaml_str);
+    fn parse(content: &str, format: TaskFormat) -> anyhow::Result<Tasks> {
+        Ok(match format {
+            TaskFormat::Yaml => serde_yaml::from_str(content)?,
+            TaskFormat::Json => serde_json5::from_str(content)?,
+        })
+    }
+
+    pub fn convert_json_to_yaml<P: AsRef<Path>>(path: P) -> anyhow::Result<()> {
+        print!(
+            "{}",
+            Self::convert(path, TaskFormat::Json, TaskFormat::Yaml)?
+        );
 
         Ok(())
     }
 
     pub fn convert_yaml_to_json<P: AsRef<Path>>(path: P) -> anyhow::Result<()> {
-        let file = std::fs::File::open(path)?;
-        let reader = std::io::BufReader::new(file);
-        let tasks: Tasks = serde_yaml::from_reader(reader)?;
+        print!(
+            "{}",
+            Self::convert(path, TaskFormat::Yaml, TaskFormat::Json)?
+        );
 
-        let json_str = serde_json::to_string_pretty(&tasks)?;
+        Ok(())
+    }
+}
 
-        println!("{}", json_str);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task_conditions::TaskCondition;
 
-        Ok(())
+    fn create_test_task(label: &str, command: &str) -> TaskEntry {
+        TaskEntry {
+            label: label.to_string(),
+            command: command.to_string(),
+            id: None,
+            conditions: None,
+            line: None,
+        }
+    }
+
+    fn create_test_tasks() -> Tasks {
+        let mut tasks = ConfigGenerator::create_example_config();
+        tasks.inputs.push(Input {
+            id: "target".to_string(),
+            options: vec!["x86".into(), "arm".into()],
+            default: Some("arm".to_string()),
+            ..Default::default()
+        });
+        tasks
+    }
+
+    #[test]
+    fn test_to_vscode_simple_task() {
+        let (config, report) = ConfigGenerator::to_vscode(&create_test_tasks());
+
+        assert_eq!(config.version, "2.0.0");
+        assert_eq!(config.tasks[0].label, "Simple task");
+        assert_eq!(config.tasks[0].task_type, "shell");
+        assert_eq!(config.tasks[0].command, "echo 'This is just a simple task'");
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_to_vscode_expands_mappings() {
+        let (config, report) = ConfigGenerator::to_vscode(&create_test_tasks());
+
+        let tasks: Vec<(&str, &str)> = config
+            .tasks
+            .iter()
+            .skip(1)
+            .map(|t| (t.label.as_str(), t.command.as_str()))
+            .collect();
+
+        assert_eq!(
+            tasks,
+            vec![
+                (
+                    "Perform build (release)",
+                    "echo 'building release in .build-release...'"
+                ),
+                (
+                    "Perform build (debug)",
+                    "echo 'building debug in .build-debug...'"
+                ),
+                (
+                    "Perform build (test)",
+                    "echo 'building test in .build-test...'"
+                ),
+            ]
+        );
+        // the expanded input is no longer referenced
+        assert!(config.inputs.is_empty());
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_to_vscode_keeps_plain_inputs() {
+        let mut tasks = create_test_tasks();
+        tasks.tasks.insert(create_test_task(
+            "Flash",
+            "flash ${input:target} ${mapping:build-dir}",
+        ));
+
+        let (config, report) = ConfigGenerator::to_vscode(&tasks);
+
+        let flash: Vec<&str> = config
+            .tasks
+            .iter()
+            .filter(|t| t.label.starts_with("Flash"))
+            .map(|t| t.command.as_str())
+            .collect();
+
+        assert_eq!(flash.len(), 3);
+        assert_eq!(flash[0], "flash ${input:target} .build-release");
+        assert_eq!(config.inputs.len(), 1);
+        assert_eq!(config.inputs[0].id, "target");
+        assert_eq!(config.inputs[0].input_type, "pickString");
+        assert_eq!(config.inputs[0].default.as_deref(), Some("arm"));
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_to_vscode_free_text_inputs() {
+        let mut tasks = create_test_tasks();
+        tasks.inputs.push(Input {
+            id: "message".to_string(),
+            default: Some("hello".to_string()),
+            ..Default::default()
+        });
+        tasks.inputs[1].allow_custom = true;
+        tasks.inputs[2].secret = true;
+        tasks.tasks.insert(create_test_task(
+            "Commit",
+            "git commit -m ${input:message} ${input:target}",
+        ));
+
+        let (config, report) = ConfigGenerator::to_vscode(&tasks);
+
+        assert_eq!(config.inputs[0].id, "message");
+        assert_eq!(config.inputs[0].input_type, "promptString");
+        assert!(config.inputs[0].options.is_empty());
+        assert!(config.inputs[0].password);
+        assert_eq!(config.inputs[1].input_type, "pickString");
+        assert!(!config.inputs[1].password);
+        assert_eq!(
+            report,
+            vec!["input 'target': custom values are not supported by pickString"]
+        );
+    }
+
+    #[test]
+    fn test_to_vscode_labelled_options() {
+        let mut tasks = create_test_tasks();
+        tasks.inputs[1].options[0].label = Some("x86 (64 bit)".to_string());
+        tasks.inputs[1].options[1].description = Some("apple silicon".to_string());
+        tasks.tasks.insert(create_test_task(
+            "Build",
+            "cargo build --target ${input:target}",
+        ));
+
+        let (config, report) = ConfigGenerator::to_vscode(&tasks);
+
+        assert_eq!(
+            config.inputs[0].options,
+            vec![
+                VsCodeOption::Labelled {
+                    label: "x86 (64 bit)".to_string(),
+                    value: "x86".to_string(),
+                },
+                VsCodeOption::Value("arm".to_string()),
+            ]
+        );
+        assert_eq!(
+            report,
+            vec!["input 'target': option descriptions are not supported"]
+        );
+    }
+
+    #[test]
+    fn test_to_vscode_reports_untranslatable() {
+        let mut tasks = create_test_tasks();
+        tasks.mappings[0].options.shift_remove("debug");
+        tasks
+            .tasks
+            .insert(create_test_task("Missing", "echo ${mapping:missing}"));
+        tasks.tasks.insert(TaskEntry {
+            conditions: Some(TaskCondition {
+                paths: vec![],
+                files: vec![".git".to_string()],
+            }),
+            ..create_test_task("Conditional", "git status")
+        });
+
+        let (config, report) = ConfigGenerator::to_vscode(&tasks);
+
+        assert!(config
+            .tasks
+            .iter()
+            .all(|t| t.label != "Perform build (debug)"));
+        assert!(config.tasks.iter().any(|t| t.label == "Conditional"));
+        assert_eq!(report.len(), 3);
+        assert!(report[0].contains("'Perform build' skipped for 'debug'"));
+        assert!(report[1].contains("'Missing' skipped"));
+        assert!(report[2].contains("conditions are not supported"));
+    }
+
+    #[test]
+    fn test_to_vscode_reports_dynamic_drivers() {
+        let mut tasks = create_test_tasks();
+        tasks.inputs[0].options.clear();
+        tasks.inputs[0].options_command = Some("ls".to_string());
+
+        let (config, report) = ConfigGenerator::to_vscode(&tasks);
+
+        assert!(config
+            .tasks
+            .iter()
+            .all(|t| !t.label.starts_with("Perform build")));
+        assert_eq!(
+            report,
+            vec!["task 'Perform build' skipped: input 'build-type' has no static options"]
+        );
+    }
+
+    #[test]
+    fn test_schema_is_up_to_date() {
+        let committed: serde_json::Value =
+            serde_json::from_str(include_str!("../../aliasx.schema.json")).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(ConfigGenerator::schema()).unwrap(),
+            committed,
+            "regenerate with: aliasx config-generator schema > aliasx.schema.json"
+        );
+    }
+
+    #[test]
+    fn test_example_config_schema_header() {
+        let yaml = ConfigGenerator::example_config(TaskFormat::Yaml).unwrap();
+
+        assert!(yaml.starts_with(&format!("# yaml-language-server: $schema={}\n", SCHEMA_URL)));
+        assert!(serde_yaml::from_str::<Tasks>(&yaml).is_ok());
     }
 }
@@ -0,0 +1,104 @@
+use anyhow::anyhow;
+use execute::Execute;
+use std::process::{Command, Stdio};
+
+use crate::task_filter::TaskFilter;
+use crate::tasks::{TaskEntry, Tasks};
+
+/// Parses the output of `git config -z --get-regexp '^alias\.'`.
+/// Each entry is terminated by NUL and the key is separated from the value by a newline.
+fn parse_git_aliases(output: &str) -> Tasks {
+    let mut tasks = Tasks::default();
+
+    for entry in output.split('\0') {
+        let Some((key, value)) = entry.split_once('\n') else {
+            continue;
+        };
+        let Some(name) = key.strip_prefix("alias.") else {
+            continue;
+        };
+
+        // shell aliases must be run through git to get the same working dir and args
+        let command = if value.trim_start().starts_with('!') {
+            format!("git {}", name)
+        } else {
+            format!("git {}", value.trim())
+        };
+
+        tasks.tasks.insert(TaskEntry {
+            label: format!("git {}", name),
+            command,
+            id: Option::None,
+            conditions: Option::None,
+            line: Option::None,
+        });
+    }
+
+    tasks
+}
+
+pub fn get_git_aliases() -> anyhow::Result<Tasks> {
+    let mut command = Command::new("git");
+    command.args(["config", "-z", "--get-regexp", r"^alias\."]);
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::null());
+
+    let output = command.execute_output()?;
+
+    let output_str = match output.status.code() {
+        Some(0) => String::from_utf8(output.stdout)?,
+        // no aliases defined
+        Some(1) => String::new(),
+        Some(code) => return Err(anyhow!("calling 'git config' failed (exit code {})", code)),
+        None => return Err(anyhow!("calling 'git config' was interrupted")),
+    };
+
+    let mut tasks = parse_git_aliases(&output_str);
+    tasks.scope = TaskFilter::Git;
+
+    Ok(tasks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commands(tasks: &Tasks) -> Vec<(&str, &str)> {
+        tasks
+            .tasks
+            .iter()
+            .map(|t| (t.label.as_str(), t.command.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_git_aliases() {
+        let output = "alias.co\ncheckout\0alias.lg\nlog --oneline --graph\0";
+        let tasks = parse_git_aliases(output);
+
+        assert_eq!(
+            commands(&tasks),
+            vec![
+                ("git co", "git checkout"),
+                ("git lg", "git log --oneline --graph")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_git_shell_alias() {
+        let output = "alias.root\n!pwd\0alias.multi\n! f() { echo \"$1\"; }; f\0";
+        let tasks = parse_git_aliases(output);
+
+        assert_eq!(
+            commands(&tasks),
+            vec![("git root", "git root"), ("git multi", "git multi")]
+        );
+    }
+
+    #[test]
+    fn test_parse_git_aliases_empty() {
+        assert!(parse_git_aliases("").tasks.is_empty());
+        assert!(parse_git_aliases("user.name\nsomeone\0").tasks.is_empty());
+    }
+}
@@ -1,13 +1,17 @@
 use std::path::PathBuf;
 
 use chrono::{DateTime, Local, Utc};
+use indexmap::IndexMap;
 use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ValueRef};
 use serde::{Deserialize, Serialize};
 
 use anyhow::{anyhow, Context};
 use rusqlite::{params, Connection};
 
+use crate::input::{Input, REDACTED};
+use crate::input_mapping::InputMapping;
 use crate::task_filter::TaskFilter;
+use crate::tasks::Tasks;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HistoryEntry {
@@ -17,18 +21,68 @@ pub struct HistoryEntry {
     pub started_at: DateTime<Utc>,
     pub exit_code: i32,
     pub scope: TaskFilter,
+
+    /// input id -> selected value
+    pub input_selections: IndexMap<String, String>,
 }
 
 impl HistoryEntry {
-    pub fn new(task_name: &str, task_command: &str, exit_code: i32, scope: TaskFilter) -> Self {
+    pub fn new(
+        task_name: &str,
+        task_command: &str,
+        exit_code: i32,
+        scope: TaskFilter,
+        input_selections: &IndexMap<String, String>,
+    ) -> Self {
         Self {
             id: 0,
             task_name: task_name.to_string(),
             task_command: task_command.to_string(),
             started_at: Utc::now(),
             exit_code,
-            scope: scope,
+            scope,
+            input_selections: input_selections.clone(),
+        }
+    }
+
+    /// Entries of tasks with secret inputs can't be re-run as stored.
+    pub fn is_redacted(&self) -> bool {
+        self.input_selections
+            .values()
+            .any(|value| value == REDACTED)
+    }
+
+    /// Builds a task command from `template`, the command of the task the entry was
+    /// created from. Inputs in `keep` stay `${input:<id>}` placeholders, the others and
+    /// the mappings only depending on them are filled in with the values of the entry.
+    pub fn promoted_command(
+        &self,
+        source: &Tasks,
+        template: &str,
+        keep: &[&str],
+    ) -> anyhow::Result<String> {
+        let filled = |id: &str| !keep.contains(&id) && self.input_selections.contains_key(id);
+        let mut command = template.to_string();
+
+        for input_id in Input::extract_variables(template) {
+            if filled(&input_id) {
+                command = command.replace(
+                    &format!("${{input:{}}}", input_id),
+                    &self.input_selections[&input_id],
+                );
+            }
+        }
+
+        for mapping_id in InputMapping::extract_from_str(template) {
+            let (mapping, _) = source.resolve_mapping(&mapping_id)?;
+            if mapping.input_ids().into_iter().all(filled) {
+                let placeholder = format!("${{mapping:{}}}", mapping_id);
+                let value = source.apply_mappings(&placeholder, &self.input_selections)?;
+                command = command.replace(&placeholder, &value);
+            }
         }
+
+        Ok(command)
     }
 }
 
@@ -50,6 +104,12 @@ impl History {
         }
 
         let conn = Connection::open(path)?;
+        Self::init(&conn)?;
+
+        Ok(conn)
+    }
+
+    fn init(conn: &Connection) -> anyhow::Result<()> {
         conn.execute_batch(
             "
             PRAGMA journal_mode=WAL;
@@ -61,10 +121,35 @@ impl History {
                 exit_code    INTEGER NOT NULL,
                 scope        TEXT    NOT NULL
             );
+            CREATE TABLE IF NOT EXISTS last_selections (
+                project    TEXT NOT NULL,
+                task       TEXT NOT NULL,
+                input      TEXT NOT NULL,
+                value      TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (project, task, input)
+            );
         ",
         )?;
 
-        Ok(conn)
+        Self::migrate(conn)
+    }
+
+    /// Adds columns introduced after the initial schema to existing databases.
+    fn migrate(conn: &Connection) -> anyhow::Result<()> {
+        let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('task_history')")?;
+        let columns = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        if !columns.iter().any(|c| c == "input_selections") {
+            conn.execute(
+                "ALTER TABLE task_history ADD COLUMN input_selections TEXT NOT NULL DEFAULT '{}'",
+                [],
+            )?;
+        }
+
+        Ok(())
     }
 
     fn get_path() -> anyhow::Result<PathBuf> {
@@ -92,7 +177,7 @@ impl History {
     pub fn load() -> anyhow::Result<Vec<HistoryEntry>> {
         let conn = Self::connect()?;
         let mut stmt = conn.prepare(
-            "SELECT id, task_name, task_command, started_at, exit_code, scope
+            "SELECT id, task_name, task_command, started_at, exit_code, scope, input_selections
          FROM task_history
          ORDER BY started_at DESC LIMIT 100",
         )?;
@@ -105,6 +190,8 @@ impl History {
                     started_at: row.get(3)?,
                     exit_code: row.get(4)?,
                     scope: row.get(5)?,
+                    input_selections: serde_json::from_str(&row.get::<_, String>(6)?)
+                        .unwrap_or_default(),
                 })
             })?
             .collect::<rusqlite::Result<Vec<_>>>()?;
@@ -123,14 +210,15 @@ impl History {
         let mut conn = Self::connect()?;
         let tx = conn.transaction()?;
         tx.execute(
-            "INSERT INTO task_history (task_name, task_command, started_at, exit_code, scope)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
+            "INSERT INTO task_history (task_name, task_command, started_at, exit_code, scope, input_selections)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
             params![
                 &entry.task_name,
                 &entry.task_command,
                 &entry.started_at,
                 &entry.exit_code,
                 entry.scope.to_string(),
+                serde_json::to_string(&entry.input_selections)?,
             ],
         )?;
 
@@ -145,9 +233,187 @@ impl History {
         Ok(())
     }
 
+    /// Selections are remembered per project: the closest directory containing `.git`,
+    /// or the current directory outside of a repository.
+    fn project_root() -> anyhow::Result<String> {
+        let cwd = std::env::current_dir()?;
+        let root = cwd
+            .ancestors()
+            .find(|dir| dir.join(".git").exists())
+            .unwrap_or(&cwd);
+
+        Ok(root.to_string_lossy().to_string())
+    }
+
+    /// Stores `selections` as the last selections of `task` in the current project.
+    pub fn remember_selections(
+        task: &str,
+        selections: &IndexMap<String, String>,
+    ) -> anyhow::Result<()> {
+        if selections.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = Self::connect()?;
+        Self::store_selections(&mut conn, &Self::project_root()?, task, selections)
+    }
+
+    /// Returns the last selections (input id -> value) of `task` in the current project.
+    pub fn last_selections(task: &str) -> anyhow::Result<IndexMap<String, String>> {
+        let conn = Self::connect()?;
+        Self::query_selections(&conn, &Self::project_root()?, task)
+    }
+
+    fn store_selections(
+        conn: &mut Connection,
+        project: &str,
+        task: &str,
+        selections: &IndexMap<String, String>,
+    ) -> anyhow::Result<()> {
+        let tx = conn.transaction()?;
+        for (input, value) in selections {
+            tx.execute(
+                "INSERT OR REPLACE INTO last_selections (project, task, input, value, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![project, task, input, value, Utc::now()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn query_selections(
+        conn: &Connection,
+        project: &str,
+        task: &str,
+    ) -> anyhow::Result<IndexMap<String, String>> {
+        let mut stmt = conn
+            .prepare("SELECT input, value FROM last_selections WHERE project = ?1 AND task = ?2")?;
+        let rows = stmt
+            .query_map(params![project, task], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<IndexMap<String, String>>>()?;
+        Ok(rows)
+    }
+
     pub fn format_timestamp(dt: &DateTime<Utc>) -> String {
         dt.with_timezone(&Local)
             .format("%Y-%m-%d %H:%M:%S")
             .to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_entry(command: &str, selections: &[(&str, &str)]) -> HistoryEntry {
+        let selections: IndexMap<String, String> = selections
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        HistoryEntry::new("task", command, 0, TaskFilter::Local, &selections)
+    }
+
+    fn create_test_source() -> Tasks {
+        serde_yaml::from_str(
+            r#"
+tasks: []
+inputs:
+  - id: env
+    options: [prod, production]
+  - id: jobs
+    options: ["1", "16"]
+mappings:
+  - id: host
+    input: env
+    options:
+      prod: prod.example.com
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_promoted_command() {
+        let entry = create_test_entry(
+            "make -j16 -l 1 deploy-production prod prod.example.com",
+            &[("env", "prod"), ("jobs", "1")],
+        );
+        let template = "make -j16 -l ${input:jobs} deploy-production ${input:env} ${mapping:host}";
+        let source = create_test_source();
+
+        assert_eq!(
+            entry
+                .promoted_command(&source, template, &["env", "jobs"])
+                .unwrap(),
+            template
+        );
+        assert_eq!(
+            entry
+                .promoted_command(&source, template, &["jobs"])
+                .unwrap(),
+            "make -j16 -l ${input:jobs} deploy-production prod prod.example.com"
+        );
+        assert_eq!(
+            entry.promoted_command(&source, template, &["env"]).unwrap(),
+            "make -j16 -l 1 deploy-production ${input:env} ${mapping:host}"
+        );
+    }
+
+    #[test]
+    fn test_promoted_command_missing_selection() {
+        let entry = create_test_entry("echo hello", &[]);
+        let source = create_test_source();
+
+        assert_eq!(
+            entry
+                .promoted_command(&source, "echo ${input:env} ${mapping:host}", &[])
+                .unwrap(),
+            "echo ${input:env} ${mapping:host}"
+        );
+    }
+
+    #[test]
+    fn test_last_selections() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        History::init(&conn).unwrap();
+
+        let selections = |pairs: &[(&str, &str)]| -> IndexMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+
+        History::store_selections(
+            &mut conn,
+            "/repo",
+            "deploy",
+            &selections(&[("env", "dev"), ("region", "eu")]),
+        )
+        .unwrap();
+        History::store_selections(
+            &mut conn,
+            "/repo",
+            "deploy",
+            &selections(&[("env", "prod")]),
+        )
+        .unwrap();
+        History::store_selections(&mut conn, "/other", "deploy", &selections(&[("env", "qa")]))
+            .unwrap();
+
+        let last = History::query_selections(&conn, "/repo", "deploy").unwrap();
+        assert_eq!(last.len(), 2);
+        assert_eq!(last["env"], "prod");
+        assert_eq!(last["region"], "eu");
+
+        assert_eq!(
+            History::query_selections(&conn, "/other", "deploy").unwrap()["env"],
+            "qa"
+        );
+        assert!(History::query_selections(&conn, "/repo", "build")
+            .unwrap()
+            .is_empty());
+    }
+}
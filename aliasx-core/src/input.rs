@@ -1,7 +1,20 @@
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Child, Stdio};
 use std::sync::LazyLock;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use regex::Regex;
-use serde::{Deserialize, Serialize};
+use anyhow::{anyhow, Context};
+use execute::shell;
+use globset::GlobBuilder;
+use indexmap::IndexMap;
+use regex::{NoExpand, Regex};
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::de::{self, value::MapAccessDeserializer, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 static FIND_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\$\{input:([^}]+)\}").expect("invalid regex"));
@@ -9,17 +22,238 @@ static FIND_REGEX: LazyLock<Regex> =
 static REPLACE_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\$\{input:[^}]+\}").expect("invalid regex"));
 
-#[derive(Hash, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Input {
+    /// referenced as `${input:<id>}`
     pub id: String,
 
+    /// shown instead of the id when prompting
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// option selected initially
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(schema_with = "scalar_schema")]
     pub default: Option<String>,
 
-    pub options: Vec<String>,
+    /// environment variable whose value is used as the default when it is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_env: Option<String>,
+
+    /// shell command run when prompting - the first line of its output is the default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_command: Option<String>,
+
+    /// options to pick from - without options the value is typed in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(schema_with = "options_schema")]
+    pub options: Vec<InputOption>,
+
+    /// also accept values that aren't among the options
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_custom: bool,
+
+    /// shell command run when prompting - each line of its output becomes an option
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options_command: Option<String>,
+
+    /// seconds to wait for `options_command` (default 5)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options_timeout: Option<u64>,
+
+    /// glob relative to the config file - the matching files become options
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options_glob: Option<String>,
+
+    /// select several options - they are joined with `separator`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub multiple: bool,
+
+    /// joins the selections of a `multiple` input (default is a space)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub separator: Option<String>,
+
+    /// start at `default` instead of the last selection
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub prefer_default: bool,
+
+    /// regex the whole value has to match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    /// the value has to be a number of at least `min`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+
+    /// the value has to be a number of at most `max`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+
+    /// reject empty values
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+
+    /// options depending on the value selected for another input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options_by: Option<OptionsBy>,
+
+    /// mask the value when typing it and keep it out of the history
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub secret: bool,
+
+    /// pass the secret to the command as this environment variable instead of inlining it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_env: Option<String>,
+}
+
+/// Options keyed by the value selected for `input`.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OptionsBy {
+    /// id of the input selected first
+    pub input: String,
+
+    /// options for each value of `input`
+    #[schemars(schema_with = "options_by_schema")]
+    pub values: IndexMap<String, Vec<InputOption>>,
+}
+
+/// An option of an input - either just the value or the value with the `label` and
+/// `description` shown when picking it.
+#[derive(PartialEq, Debug, Clone)]
+pub struct InputOption {
+    pub value: String,
+    pub label: Option<String>,
+    pub description: Option<String>,
+}
+
+/// The object form of [`InputOption`].
+#[derive(Serialize, Deserialize)]
+struct LabelledOption {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+impl InputOption {
+    /// Text shown when picking the option.
+    pub fn label(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.value)
+    }
+}
+
+impl From<String> for InputOption {
+    fn from(value: String) -> Self {
+        Self {
+            value,
+            label: None,
+            description: None,
+        }
+    }
+}
+
+impl From<&str> for InputOption {
+    fn from(value: &str) -> Self {
+        Self::from(value.to_string())
+    }
+}
+
+// plain options are written as scalars to keep configs short
+impl Serialize for InputOption {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.label.is_none() && self.description.is_none() {
+            return serializer.serialize_str(&self.value);
+        }
+
+        LabelledOption {
+            label: self.label.clone(),
+            value: self.value.clone(),
+            description: self.description.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for InputOption {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(InputOptionVisitor)
+    }
+}
+
+struct InputOptionVisitor;
+
+impl<'de> Visitor<'de> for InputOptionVisitor {
+    type Value = InputOption;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a scalar or an object with label, value and description")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(InputOption::from(v))
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(InputOption::from(v.to_string()))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(InputOption::from(v.to_string()))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(InputOption::from(v.to_string()))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(InputOption::from(format!("{:?}", v)))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        let option = LabelledOption::deserialize(MapAccessDeserializer::new(map))?;
+        Ok(InputOption {
+            value: option.value,
+            label: option.label,
+            description: option.description,
+        })
+    }
+}
+
+/// Shown and stored instead of secret values.
+pub const REDACTED: &str = "***";
+
+const DEFAULT_OPTIONS_TIMEOUT: u64 = 5;
+const DEFAULT_SEPARATOR: &str = " ";
+
+/// yaml configs may use numbers and booleans as options - they are read as strings
+pub(crate) fn scalar_schema(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({ "type": ["string", "number", "boolean"] })
+}
+
+fn option_schema(generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            scalar_schema(generator),
+            {
+                "type": "object",
+                "properties": {
+                    "label": { "type": "string", "description": "shown instead of the value" },
+                    "value": scalar_schema(generator),
+                    "description": { "type": "string", "description": "shown next to the label" }
+                },
+                "required": ["value"]
+            }
+        ]
+    })
+}
+
+fn options_schema(generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({ "type": "array", "items": option_schema(generator) })
+}
+
+fn options_by_schema(generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({ "type": "object", "additionalProperties": options_schema(generator) })
 }
 
 // Input is defined as ${<type>:<id>}
@@ -32,17 +266,480 @@ impl Input {
     }
 
     pub fn replace_next_variable(s: &str, replacement: &str) -> String {
-        REPLACE_REGEX.replace(s, replacement).to_string()
+        REPLACE_REGEX.replace(s, NoExpand(replacement)).to_string()
     }
 
     pub fn get_default_selection(&self) -> usize {
         self.default
             .as_deref()
-            .and_then(|d| self.options.iter().position(|opt| opt == d))
+            .and_then(|d| self.options.iter().position(|opt| opt.value == d))
             .unwrap_or(0)
     }
 
+    /// Options selected initially for `multiple` inputs - `default` may list several.
+    pub fn get_default_selections(&self) -> Vec<usize> {
+        self.default
+            .as_deref()
+            .map(|d| {
+                self.split_value(d)
+                    .into_iter()
+                    .filter_map(|v| self.options.iter().position(|opt| opt.value == v))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn option_values(&self) -> Vec<&str> {
+        self.options.iter().map(|o| o.value.as_str()).collect()
+    }
+
+    pub fn separator(&self) -> &str {
+        self.separator.as_deref().unwrap_or(DEFAULT_SEPARATOR)
+    }
+
+    pub fn join_values(&self, values: &[String]) -> String {
+        values.join(self.separator())
+    }
+
+    /// Splits a selection back into its elements - only `multiple` inputs have more than one.
+    pub fn split_value<'a>(&self, value: &'a str) -> Vec<&'a str> {
+        if !self.multiple {
+            return vec![value];
+        }
+
+        value
+            .split(self.separator())
+            .filter(|v| !v.is_empty())
+            .collect()
+    }
+
+    /// Environment variable presetting the value, eg. `ALIASX_INPUT_BUILD_TYPE` for `build-type`.
+    pub fn env_var(&self) -> String {
+        let id: String = self
+            .id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        format!("ALIASX_INPUT_{}", id)
+    }
+
+    /// Checks that `value` (each element for `multiple` inputs) is one of the options
+    /// and satisfies the rules. Expects the options to be resolved.
+    pub fn check_value(&self, value: &str) -> anyhow::Result<()> {
+        if !self.accepts_custom() {
+            for element in self.split_value(value) {
+                if !self.options.iter().any(|o| o.value == element) {
+                    return Err(anyhow!(
+                        "invalid value '{}' for input '{}' (options: {})",
+                        self.shown(element),
+                        self.id,
+                        self.option_values().join(", ")
+                    ));
+                }
+            }
+        }
+
+        self.check_rules(value).map_err(|err| {
+            anyhow!(
+                "invalid value '{}' for input '{}': {}",
+                self.shown(value),
+                self.id,
+                err
+            )
+        })
+    }
+
+    /// Checks a value typed in instead of picked. For `multiple` inputs it is a single element,
+    /// so it must not contain the separator, which would split it into several values.
+    pub fn check_custom(&self, value: &str) -> anyhow::Result<()> {
+        if self.multiple && value.contains(self.separator()) {
+            return Err(anyhow!(
+                "'{}' contains the separator '{}'",
+                self.shown(value),
+                self.separator()
+            ));
+        }
+
+        self.check_rules(value)
+    }
+
+    /// Checks `value` (each element for `multiple` inputs) against `required`, `pattern`
+    /// and `min`/`max`. Empty values only fail when the input is required.
+    pub fn check_rules(&self, value: &str) -> anyhow::Result<()> {
+        if value.trim().is_empty() && self.required {
+            return Err(anyhow!("a value is required"));
+        } else if value.trim().is_empty() {
+            return Ok(());
+        }
+
+        let pattern = self
+            .pattern
+            .as_deref()
+            .map(|p| Regex::new(&format!("^(?:{})$", p)))
+            .transpose()
+            .context("invalid pattern")?;
+
+        for element in self.split_value(value) {
+            if let Some(pattern) = &pattern {
+                if !pattern.is_match(element) {
+                    return Err(anyhow!(
+                        "'{}' doesn't match the pattern '{}'",
+                        self.shown(element),
+                        self.pattern.as_deref().unwrap_or_default()
+                    ));
+                }
+            }
+
+            if self.min.is_none() && self.max.is_none() {
+                continue;
+            }
+
+            let number: f64 = element
+                .trim()
+                .parse()
+                .map_err(|_| anyhow!("'{}' is not a number", self.shown(element)))?;
+
+            if let Some(min) = self.min.filter(|min| number < *min) {
+                return Err(anyhow!(
+                    "'{}' is less than the minimum {}",
+                    self.shown(element),
+                    min
+                ));
+            }
+
+            if let Some(max) = self.max.filter(|max| number > *max) {
+                return Err(anyhow!(
+                    "'{}' is more than the maximum {}",
+                    self.shown(element),
+                    max
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the rules can be applied and `default` satisfies them.
+    pub fn validate_rules(&self) -> anyhow::Result<()> {
+        if let Some(pattern) = &self.pattern {
+            Regex::new(pattern).with_context(|| format!("invalid pattern '{}'", pattern))?;
+        }
+
+        if let (Some(min), Some(max)) = (self.min, self.max) {
+            if min > max {
+                return Err(anyhow!("min {} is larger than max {}", min, max));
+            }
+        }
+
+        if let Some(default) = &self.default {
+            self.check_rules(default)
+                .with_context(|| format!("default '{}' is invalid", self.shown(default)))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn is_secret(&self) -> bool {
+        self.secret || self.secret_env.is_some()
+    }
+
+    /// `value` as it may be shown - secrets are redacted.
+    pub fn shown<'a>(&self, value: &'a str) -> &'a str {
+        if self.is_secret() {
+            REDACTED
+        } else {
+            value
+        }
+    }
+
+    /// Inputs without options are typed in instead of picked.
+    pub fn is_free_text(&self) -> bool {
+        self.options.is_empty()
+            && self.options_command.is_none()
+            && self.options_glob.is_none()
+            && self.options_by.is_none()
+    }
+
+    pub fn accepts_custom(&self) -> bool {
+        self.allow_custom || self.is_free_text()
+    }
+
+    fn extend_options(&mut self, options: impl IntoIterator<Item = InputOption>) {
+        for option in options {
+            if !self.options.iter().any(|o| o.value == option.value) {
+                self.options.push(option);
+            }
+        }
+    }
 
+    /// Returns a copy with `default` taken from `default_env` if it is set, or else from the
+    /// output of `default_command`, and whether one of them supplied it. The static `default`
+    /// is kept if neither is configured or the variable isn't set.
+    pub fn resolve_default(&self) -> anyhow::Result<(Input, bool)> {
+        let mut resolved = self.clone();
+        let var = resolved.default_env.take();
+        let command = resolved.default_command.take();
+
+        if let Some(value) = var
+            .and_then(|var| std::env::var(var).ok())
+            .filter(|value| !value.is_empty())
+        {
+            resolved.default = Some(value);
+            return Ok((resolved, true));
+        }
+
+        if let Some(command) = command {
+            let timeout =
+                Duration::from_secs(self.options_timeout.unwrap_or(DEFAULT_OPTIONS_TIMEOUT));
+            let output = run_with_timeout(&command, timeout)
+                .with_context(|| format!("default_command '{}' failed", command))?;
+            let line = output
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .ok_or_else(|| anyhow!("default_command '{}' printed nothing", command))?;
+
+            resolved.default = Some(line.to_string());
+            return Ok((resolved, true));
+        }
+
+        Ok((resolved, false))
+    }
+
+    /// Returns a copy with the `options_by` options for the value selected for the other
+    /// input appended - all of them while that input isn't selected yet.
+    pub fn select_options_by(
+        &self,
+        selections: &IndexMap<String, String>,
+    ) -> anyhow::Result<Input> {
+        let mut selected = self.clone();
+        let Some(by) = selected.options_by.take() else {
+            return Ok(selected);
+        };
+
+        match selections.get(&by.input) {
+            Some(value) => match by.values.get(value) {
+                Some(options) => selected.extend_options(options.iter().cloned()),
+                None if !self.allow_custom && selected.is_free_text() => {
+                    return Err(anyhow!(
+                        "no options for input '{}' when '{}' is '{}'",
+                        self.id,
+                        by.input,
+                        value
+                    ))
+                }
+                None => {}
+            },
+            None => selected.extend_options(by.values.into_values().flatten()),
+        }
+
+        Ok(selected)
+    }
+
+    /// Returns a copy with the files matching `options_glob` (relative to `config_dir`)
+    /// and the output of `options_command` appended to the options.
+    /// Files are written as paths from the current directory, where commands run.
+    pub fn resolve_options(&self, config_dir: &Path) -> anyhow::Result<Input> {
+        let mut resolved = self.clone();
+
+        if let Some(pattern) = resolved.options_glob.take() {
+            let files = glob_files(config_dir, &pattern)
+                .with_context(|| format!("options_glob '{}' failed", pattern))?;
+            resolved.extend_options(files.into_iter().map(|file| {
+                if config_dir == Path::new(".") {
+                    InputOption::from(file)
+                } else {
+                    InputOption::from(config_dir.join(file).to_string_lossy().into_owned())
+                }
+            }));
+        }
+
+        if let Some(command) = resolved.options_command.take() {
+            let timeout =
+                Duration::from_secs(self.options_timeout.unwrap_or(DEFAULT_OPTIONS_TIMEOUT));
+            let output = run_with_timeout(&command, timeout)
+                .with_context(|| format!("options_command '{}' failed", command))?;
+
+            resolved.extend_options(
+                output
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(InputOption::from),
+            );
+        }
+
+        if resolved.options.is_empty() && !self.is_free_text() {
+            let sources: Vec<String> = [
+                self.options_glob
+                    .as_ref()
+                    .map(|g| format!("options_glob '{}'", g)),
+                self.options_command
+                    .as_ref()
+                    .map(|c| format!("options_command '{}'", c)),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+
+            return Err(anyhow!("no options found by {}", sources.join(" or ")));
+        }
+
+        Ok(resolved)
+    }
+}
+
+fn is_glob_literal(component: &str) -> bool {
+    !component.contains(['*', '?', '[', '{', '\\'])
+}
+
+/// Directories `options_glob` doesn't descend into unless the pattern names them,
+/// eg. `target/*.bin`.
+const SKIPPED_DIRS: &[&str] = &[".git", ".hg", ".svn", "target", "node_modules"];
+
+/// Files looked at for one `options_glob` before giving up.
+const MAX_GLOB_FILES: usize = 10_000;
+
+/// Returns the files below `dir` matching `pattern` as sorted, `/` separated relative paths.
+/// `*` doesn't match across directories, use `**` for that.
+fn glob_files(dir: &Path, pattern: &str) -> anyhow::Result<Vec<String>> {
+    let matcher = GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()?
+        .compile_matcher();
+
+    // only walk below the literal part of the pattern, eg. `configs` for `configs/**/*.yaml`
+    let components: Vec<&str> = pattern.split('/').collect();
+    let literal = components
+        .iter()
+        .take_while(|c| is_glob_literal(c))
+        .count()
+        .min(components.len() - 1);
+    let max_depth = (!pattern.contains("**")).then_some(components.len() - literal);
+
+    let prefix = components[..literal].join("/");
+    let mut files = Vec::new();
+    walk_files(&dir.join(&prefix), &prefix, max_depth, &mut files)?;
+
+    let mut matched: Vec<String> = files.into_iter().filter(|f| matcher.is_match(f)).collect();
+    matched.sort();
+
+    Ok(matched)
+}
+
+fn walk_files(
+    dir: &Path,
+    relative: &str,
+    max_depth: Option<usize>,
+    files: &mut Vec<String>,
+) -> anyhow::Result<()> {
+    if max_depth == Some(0) {
+        return Ok(());
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+
+    for entry in entries.flatten() {
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        let path = if relative.is_empty() {
+            name.clone()
+        } else {
+            format!("{}/{}", relative, name)
+        };
+
+        // symlinked directories are not followed to avoid loops
+        match entry.file_type() {
+            Ok(t) if t.is_dir() && !SKIPPED_DIRS.contains(&name.as_str()) => {
+                walk_files(&entry.path(), &path, max_depth.map(|d| d - 1), files)?
+            }
+            Ok(t) if !t.is_dir() && entry.path().is_file() => files.push(path),
+            _ => {}
+        }
+
+        if files.len() > MAX_GLOB_FILES {
+            return Err(anyhow!(
+                "more than {} files below {:?}, use a more specific pattern",
+                MAX_GLOB_FILES,
+                dir
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn read_pipe(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut out = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut out);
+        }
+        out
+    })
+}
+
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> anyhow::Result<i32> {
+    let started = Instant::now();
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status.code().unwrap_or(-1));
+        }
+
+        if started.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow!("timed out after {}s", timeout.as_secs()));
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Runs `command` in the shell and returns its stdout. Fails on a non-zero exit
+/// status (with the first line of stderr) or when `timeout` is exceeded.
+fn run_with_timeout(command: &str, timeout: Duration) -> anyhow::Result<String> {
+    let mut child = shell(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to execute command")?;
+
+    // pipes are drained in the background so a chatty command can't block on a full pipe
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let code = wait_with_timeout(&mut child, timeout)?;
+
+    if code != 0 {
+        let stderr = stderr.join().unwrap_or_default();
+        let reason = stderr.lines().next().unwrap_or_default().trim().to_string();
+        return Err(if reason.is_empty() {
+            anyhow!("command exited with non-zero status (err={})", code)
+        } else {
+            anyhow!(
+                "command exited with non-zero status (err={}): {}",
+                code,
+                reason
+            )
+        });
+    }
+
+    stdout
+        .join()
+        .map_err(|_| anyhow!("failed to read command output"))
 }
 
 #[cfg(test)]
@@ -63,7 +760,39 @@ mod tests {
         assert_eq!(values.len(), 1);
         assert_eq!(values[0].id, "some-type");
         assert_eq!(values[0].description, Some("some description".to_string()));
-        assert_eq!(values[0].options, vec!["1", "2"]);
+        assert_eq!(values[0].option_values(), vec!["1", "2"]);
+    }
+
+    #[test]
+    fn test_input_parsing_labelled_options() {
+        let yaml = r#"
+        - id: env
+          default: prod
+          options:
+              - dev
+              - 42
+              - label: Production
+                value: prod
+                description: live cluster
+        "#;
+
+        let values: Vec<Input> = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(values[0].option_values(), vec!["dev", "42", "prod"]);
+        assert_eq!(values[0].options[2].label(), "Production");
+        assert_eq!(values[0].options[0].label(), "dev");
+        assert_eq!(
+            values[0].options[2].description.as_deref(),
+            Some("live cluster")
+        );
+        assert_eq!(values[0].get_default_selection(), 2);
+
+        let json = r#"[{ "id": "env", "options": [1, true, { "label": "Dev", "value": "dev" }] }]"#;
+        let values: Vec<Input> = serde_json::from_str(json).unwrap();
+        assert_eq!(values[0].option_values(), vec!["1", "true", "dev"]);
+
+        // plain options stay scalars
+        let json = serde_json::to_string(&values[0].options).unwrap();
+        assert_eq!(json, r#"["1","true",{"label":"Dev","value":"dev"}]"#);
     }
 
     #[test]
@@ -107,7 +836,7 @@ mod tests {
         assert_eq!(values.len(), 1);
         assert_eq!(values[0].id, "choice");
         assert_eq!(values[0].description, None);
-        assert_eq!(values[0].options, vec!["option1", "option2"]);
+        assert_eq!(values[0].option_values(), vec!["option1", "option2"]);
     }
 
     #[test]
@@ -216,9 +945,9 @@ mod tests {
     fn test_get_default_selection_first() {
         let input = Input {
             id: "test".to_string(),
-            options: vec!["a".to_string(), "b".to_string(), "c".to_string()],
-            description: None,
+            options: vec!["a".into(), "b".into(), "c".into()],
             default: Some("a".to_string()),
+            ..Default::default()
         };
         assert_eq!(input.get_default_selection(), 0);
     }
@@ -227,10 +956,358 @@ mod tests {
     fn test_get_default_selection_last() {
         let input = Input {
             id: "test".to_string(),
-            options: vec!["a".to_string(), "b".to_string(), "c".to_string()],
-            description: None,
+            options: vec!["a".into(), "b".into(), "c".into()],
             default: Some("c".to_string()),
+            ..Default::default()
         };
         assert_eq!(input.get_default_selection(), 2);
     }
+
+    #[test]
+    fn test_input_free_text() {
+        let yaml = r#"
+        - id: message
+          default: hello
+        - id: branch
+          options:
+              - main
+          allow_custom: true
+        "#;
+
+        let values: Vec<Input> = serde_yaml::from_str(yaml).unwrap();
+        assert!(values[0].options.is_empty());
+        assert!(values[0].is_free_text());
+        assert!(values[0].accepts_custom());
+
+        assert!(!values[1].is_free_text());
+        assert!(values[1].accepts_custom());
+
+        let yaml = serde_yaml::to_string(&values[0]).unwrap();
+        assert_eq!(yaml, "id: message\ndefault: hello\n");
+    }
+
+    fn create_command_input(command: &str, options: Vec<&str>) -> Input {
+        Input {
+            id: "test".to_string(),
+            options: options.iter().map(|s| InputOption::from(*s)).collect(),
+            options_command: Some(command.to_string()),
+            options_timeout: Some(1),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_options_command() {
+        let input = create_command_input("printf 'b\\n\\n c \\na\\n'", vec!["a"]);
+        assert!(!input.is_free_text());
+
+        let resolved = input.resolve_options(Path::new(".")).unwrap();
+        assert_eq!(resolved.option_values(), vec!["a", "b", "c"]);
+        assert_eq!(resolved.options_command, None);
+    }
+
+    #[test]
+    fn test_resolve_options_command_failure() {
+        let input = create_command_input("echo broken >&2; exit 3", vec![]);
+        let err = format!("{:#}", input.resolve_options(Path::new(".")).unwrap_err());
+        assert!(err.contains("err=3"), "{}", err);
+        assert!(err.contains("broken"), "{}", err);
+
+        let input = create_command_input("true", vec![]);
+        let err = format!("{:#}", input.resolve_options(Path::new(".")).unwrap_err());
+        assert!(err.contains("no options found"), "{}", err);
+    }
+
+    #[test]
+    fn test_resolve_options_command_timeout() {
+        let input = create_command_input("sleep 5", vec![]);
+
+        let started = Instant::now();
+        let err = format!("{:#}", input.resolve_options(Path::new(".")).unwrap_err());
+        assert!(err.contains("timed out after 1s"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_glob_files() {
+        let dir = std::env::temp_dir().join(format!("aliasx-glob-{}", std::process::id()));
+        for file in [
+            "configs/b.yaml",
+            "configs/a.yaml",
+            "configs/nested/c.yaml",
+            "configs/notes.txt",
+            "other/d.yaml",
+            "target/e.yaml",
+            "web/node_modules/f.yaml",
+        ] {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        let flat = glob_files(&dir, "configs/*.yaml").unwrap();
+        let nested = glob_files(&dir, "configs/**/*.yaml").unwrap();
+        let all = glob_files(&dir, "**/*.yaml").unwrap();
+        let target = glob_files(&dir, "target/*.yaml").unwrap();
+        let resolved = Input {
+            options_glob: Some("other/*.yaml".to_string()),
+            ..serde_yaml::from_str("id: file").unwrap()
+        }
+        .resolve_options(&dir)
+        .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(flat, vec!["configs/a.yaml", "configs/b.yaml"]);
+        assert_eq!(
+            nested,
+            vec!["configs/a.yaml", "configs/b.yaml", "configs/nested/c.yaml"]
+        );
+        assert_eq!(all.len(), 4);
+        assert_eq!(target, vec!["target/e.yaml"]);
+        assert_eq!(
+            resolved.options[0].value,
+            dir.join("other/d.yaml").to_string_lossy()
+        );
+        assert!(glob_files(&dir, "configs/[.yaml").is_err());
+    }
+
+    #[test]
+    fn test_input_multiple() {
+        let yaml = r#"
+        - id: packages
+          multiple: true
+          separator: ','
+          default: core,cli,missing
+          options:
+              - core
+              - tui
+              - cli
+        - id: tests
+          multiple: true
+          options:
+              - a
+        "#;
+
+        let values: Vec<Input> = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(values[0].get_default_selections(), vec![0, 2]);
+        assert_eq!(
+            values[0].join_values(&["core".to_string(), "tui".to_string()]),
+            "core,tui"
+        );
+        assert_eq!(values[0].split_value("core,tui"), vec!["core", "tui"]);
+
+        assert_eq!(values[1].separator(), " ");
+        assert_eq!(values[1].split_value("a  b"), vec!["a", "b"]);
+        assert!(values[1].get_default_selections().is_empty());
+    }
+
+    #[test]
+    fn test_split_value_single() {
+        let input = create_command_input("true", vec![]);
+        assert_eq!(input.split_value("a b"), vec!["a b"]);
+    }
+
+    #[test]
+    fn test_env_var() {
+        let mut input = create_command_input("true", vec![]);
+        input.id = "build-type.2".to_string();
+        assert_eq!(input.env_var(), "ALIASX_INPUT_BUILD_TYPE_2");
+    }
+
+    #[test]
+    fn test_check_value() {
+        let mut input = create_command_input("true", vec!["dev", "prod"]);
+        assert!(input.check_value("dev").is_ok());
+
+        let err = input.check_value("qa").unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "invalid value 'qa' for input 'test' (options: dev, prod)"
+        );
+
+        input.multiple = true;
+        assert!(input.check_value("dev prod").is_ok());
+        assert!(input.check_value("dev qa").is_err());
+
+        input.allow_custom = true;
+        assert!(input.check_value("qa").is_ok());
+        assert!(input.check_custom("qa").is_ok());
+        assert_eq!(
+            input.check_custom("qa dev").unwrap_err().to_string(),
+            "'qa dev' contains the separator ' '"
+        );
+
+        input.multiple = false;
+        assert!(input.check_custom("qa dev").is_ok());
+    }
+
+    #[test]
+    fn test_check_rules() {
+        let yaml = r#"
+        - id: port
+          min: 1024
+          max: 65535
+        - id: ticket
+          required: true
+          pattern: '[A-Z]+-[0-9]+'
+        - id: tags
+          multiple: true
+          pattern: '[a-z]+'
+        "#;
+
+        let values: Vec<Input> = serde_yaml::from_str(yaml).unwrap();
+        let [port, ticket, tags] = values.as_slice() else {
+            panic!("expected 3 inputs");
+        };
+
+        assert!(port.check_rules("8080").is_ok());
+        assert!(port.check_rules("").is_ok());
+        assert_eq!(
+            port.check_rules("80").unwrap_err().to_string(),
+            "'80' is less than the minimum 1024"
+        );
+        assert_eq!(
+            port.check_rules("99999").unwrap_err().to_string(),
+            "'99999' is more than the maximum 65535"
+        );
+        assert_eq!(
+            port.check_rules("http").unwrap_err().to_string(),
+            "'http' is not a number"
+        );
+
+        assert!(ticket.check_rules("ABC-12").is_ok());
+        assert_eq!(
+            ticket.check_rules(" ").unwrap_err().to_string(),
+            "a value is required"
+        );
+        // the whole value has to match
+        assert!(ticket.check_rules("see ABC-12").is_err());
+
+        assert!(tags.check_rules("a b").is_ok());
+        assert!(tags.check_rules("a B").is_err());
+
+        let err = ticket.check_value("abc").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value 'abc' for input 'ticket': 'abc' doesn't match the pattern '[A-Z]+-[0-9]+'"
+        );
+    }
+
+    #[test]
+    fn test_validate_rules() {
+        let mut input = create_command_input("true", vec![]);
+        input.default = Some("8080".to_string());
+        input.min = Some(1024.0);
+        assert!(input.validate_rules().is_ok());
+
+        input.max = Some(100.0);
+        assert_eq!(
+            input.validate_rules().unwrap_err().to_string(),
+            "min 1024 is larger than max 100"
+        );
+
+        input.max = None;
+        input.default = Some("80".to_string());
+        assert_eq!(
+            format!("{:#}", input.validate_rules().unwrap_err()),
+            "default '80' is invalid: '80' is less than the minimum 1024"
+        );
+
+        input.pattern = Some("[0-9".to_string());
+        assert!(input.validate_rules().is_err());
+    }
+
+    #[test]
+    fn test_select_options_by() {
+        let mut input = create_command_input("true", vec![]);
+        input.options_command = None;
+        input.options_by = Some(OptionsBy {
+            input: "env".to_string(),
+            values: IndexMap::from([
+                ("dev".into(), vec!["local".into()]),
+                ("prod".into(), vec!["us".into(), "eu".into()]),
+            ]),
+        });
+        assert!(!input.is_free_text());
+
+        let selections = IndexMap::from([("env".to_string(), "prod".to_string())]);
+        let selected = input.select_options_by(&selections).unwrap();
+        assert_eq!(selected.option_values(), vec!["us", "eu"]);
+        assert_eq!(selected.options_by, None);
+
+        // all options until env is selected
+        let selected = input.select_options_by(&IndexMap::new()).unwrap();
+        assert_eq!(selected.option_values(), vec!["local", "us", "eu"]);
+
+        let selections = IndexMap::from([("env".to_string(), "qa".to_string())]);
+        assert_eq!(
+            input
+                .select_options_by(&selections)
+                .unwrap_err()
+                .to_string(),
+            "no options for input 'test' when 'env' is 'qa'"
+        );
+
+        input.allow_custom = true;
+        assert!(input.select_options_by(&selections).unwrap().is_free_text());
+    }
+
+    #[test]
+    fn test_check_value_secret() {
+        let mut input = create_command_input("true", vec![]);
+        input.options_command = None;
+        input.pattern = Some("[a-z]+".to_string());
+        input.secret_env = Some("TOKEN".to_string());
+        assert!(input.is_secret());
+
+        let err = input.check_value("Hunter2").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value '***' for input 'test': '***' doesn't match the pattern '[a-z]+'"
+        );
+    }
+
+    #[test]
+    fn test_resolve_default() {
+        let mut input = create_command_input("true", vec!["main", "develop"]);
+        input.options_command = None;
+        input.default = Some("main".to_string());
+
+        // static default only
+        let (resolved, dynamic) = input.resolve_default().unwrap();
+        assert_eq!(resolved.default.as_deref(), Some("main"));
+        assert!(!dynamic);
+
+        // an unset variable without a command keeps the static default
+        input.default_env = Some("ALIASX_TEST_RESOLVE_DEFAULT_UNSET".to_string());
+        let (resolved, dynamic) = input.resolve_default().unwrap();
+        assert_eq!(resolved.default.as_deref(), Some("main"));
+        assert_eq!(resolved.default_env, None);
+        assert!(!dynamic);
+        input.default_env = None;
+
+        input.default_command = Some("printf '\\n  develop\\nother\\n'".to_string());
+        let (resolved, dynamic) = input.resolve_default().unwrap();
+        assert_eq!(resolved.default.as_deref(), Some("develop"));
+        assert!(dynamic);
+        assert_eq!(resolved.get_default_selection(), 1);
+        assert_eq!(resolved.default_command, None);
+
+        // the environment wins over the command
+        std::env::set_var("ALIASX_TEST_RESOLVE_DEFAULT", "feature");
+        input.default_env = Some("ALIASX_TEST_RESOLVE_DEFAULT".to_string());
+        input.default_command = Some("exit 1".to_string());
+        let (resolved, dynamic) = input.resolve_default().unwrap();
+        assert_eq!(resolved.default.as_deref(), Some("feature"));
+        assert!(dynamic);
+
+        input.default_env = Some("ALIASX_TEST_RESOLVE_DEFAULT_UNSET".to_string());
+        assert!(input.resolve_default().is_err());
+
+        input.default_command = Some("true".to_string());
+        assert_eq!(
+            input.resolve_default().unwrap_err().to_string(),
+            "default_command 'true' printed nothing"
+        );
+    }
 }
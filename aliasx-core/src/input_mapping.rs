@@ -1,19 +1,145 @@
+use std::fmt;
 use std::sync::LazyLock;
 
 use anyhow::anyhow;
 use indexmap::{IndexMap, IndexSet};
 use regex::Regex;
-use serde::{Deserialize, Serialize};
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::input::scalar_schema;
+
+/// Joins the values of a composite mapping's inputs into an options key, eg. `prod/eu`.
+pub const KEY_SEPARATOR: &str = "/";
 
 static FIND_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\$\{mapping:([^}]+)\}").expect("invalid regex"));
 
-// InputMapping is defined as ${mapping:<id>}
-#[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
+fn value_schema(generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            scalar_schema(generator),
+            { "type": "object", "additionalProperties": scalar_schema(generator) }
+        ]
+    })
+}
+
+fn options_schema(generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({ "type": "object", "additionalProperties": value_schema(generator) })
+}
+
+/// A mapped value - plain text or named fields referenced as `${mapping:<id>.<field>}`.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum MappingValue {
+    Text(String),
+    Fields(IndexMap<String, String>),
+}
+
+impl MappingValue {
+    /// The text (when `field` is `None`) or the named field of the value.
+    pub fn get(&self, field: Option<&str>) -> Option<&str> {
+        match (self, field) {
+            (Self::Text(text), None) => Some(text),
+            (Self::Fields(fields), Some(field)) => fields.get(field).map(String::as_str),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for MappingValue {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl Serialize for MappingValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Text(text) => serializer.serialize_str(text),
+            Self::Fields(fields) => fields.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MappingValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(MappingValueVisitor)
+    }
+}
+
+struct MappingValueVisitor;
+
+impl<'de> Visitor<'de> for MappingValueVisitor {
+    type Value = MappingValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a scalar or an object of named scalar fields")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(MappingValue::from(v))
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(MappingValue::Text(v.to_string()))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(MappingValue::Text(v.to_string()))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(MappingValue::Text(v.to_string()))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(MappingValue::Text(format!("{:?}", v)))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut fields = IndexMap::new();
+        while let Some((name, value)) = map.next_entry::<String, MappingValue>()? {
+            match value {
+                MappingValue::Text(text) => fields.insert(name, text),
+                MappingValue::Fields(_) => {
+                    return Err(de::Error::custom(format!(
+                        "mapping field '{}' must be a scalar",
+                        name
+                    )))
+                }
+            };
+        }
+        Ok(MappingValue::Fields(fields))
+    }
+}
+
+// InputMapping is defined as ${mapping:<id>} or ${mapping:<id>.<field>}
+#[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct InputMapping {
+    /// referenced as `${mapping:<id>}`
     pub id: String,
-    pub input: String,
-    pub options: IndexMap<String, String>,
+
+    /// id of the input the mapping depends on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+
+    /// ids of the inputs a composite mapping depends on, options are keyed as `<value>/<value>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<String>,
+
+    /// input option -> mapped value (or named fields)
+    #[schemars(schema_with = "options_schema")]
+    pub options: IndexMap<String, MappingValue>,
+
+    /// value used for selections missing from `options`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(schema_with = "value_schema")]
+    pub default: Option<MappingValue>,
+
+    /// use the selected value itself when it is missing from `options`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub passthrough: bool,
 }
 
 /*
@@ -31,8 +157,64 @@ pub struct InputMapping {
 *
 * The mapping will be replaced for each element of the option selected meaning that
 * you can build dynamic commands based on user input selections.
+*
+* Options may also map to named fields, eg. "optionA" -> { dir: "a", flags: "-x" },
+* which are referenced as ${mapping:type1.dir} and ${mapping:type1.flags}.
  */
 impl InputMapping {
+    /// Ids of the inputs the mapping depends on.
+    pub fn input_ids(&self) -> Vec<&str> {
+        if self.inputs.is_empty() {
+            self.input.iter().map(String::as_str).collect()
+        } else {
+            self.inputs.iter().map(String::as_str).collect()
+        }
+    }
+
+    pub fn is_composite(&self) -> bool {
+        self.inputs.len() > 1
+    }
+
+    /// Options key for the values of the mapping's inputs (in the order of `input_ids`).
+    pub fn key(values: &[&str]) -> String {
+        values.join(KEY_SEPARATOR)
+    }
+
+    /// Mapped value (or `field` of it) for a selection, falling back to `default` and then `passthrough`.
+    pub fn map_value(&self, value: &str, field: Option<&str>) -> anyhow::Result<String> {
+        let Some(mapped) = self.options.get(value).or(self.default.as_ref()) else {
+            return if self.passthrough {
+                Ok(value.to_string())
+            } else {
+                Err(anyhow!(
+                    "no mapping found for selection '{}' in input '{}'",
+                    value,
+                    Self::key(&self.input_ids())
+                ))
+            };
+        };
+
+        mapped.get(field).map(String::from).ok_or_else(|| match field {
+            Some(field) => anyhow!(
+                "mapping '{}' has no field '{}' for selection '{}'",
+                self.id,
+                field,
+                value
+            ),
+            None => anyhow!(
+                "mapping '{}' has fields for selection '{}', reference one as ${{mapping:{}.<field>}}",
+                self.id,
+                value,
+                self.id
+            ),
+        })
+    }
+
+    /// True when selections missing from `options` still produce a value.
+    pub fn has_fallback(&self) -> bool {
+        self.default.is_some() || self.passthrough
+    }
+
     pub fn extract_from_str(s: &str) -> IndexSet<String> {
         FIND_REGEX
             .captures_iter(s)
@@ -1,8 +1,13 @@
+pub mod alias_cache;
 pub mod aliases;
+pub mod config_document;
+pub mod config_editor;
 pub mod config_generator;
+pub mod git_aliases;
 pub mod history;
 pub mod input;
 pub mod input_mapping;
+pub mod shell_export;
 pub mod task_collection;
 pub mod task_conditions;
 pub mod task_filter;
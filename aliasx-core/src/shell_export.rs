@@ -0,0 +1,154 @@
+use indexmap::IndexSet;
+
+use crate::aliases::ShellKind;
+use crate::task_collection::TaskCollection;
+
+/// Generates a sourceable shell file with one function per task id.
+///
+/// Every function delegates to `aliasx run <id> -- <args>`, so a task whose command starts
+/// with its own id (eg. `make` -> `make -j8`) doesn't call itself and the command always
+/// runs in the shell aliasx uses, whatever shell the function is defined in.
+pub struct ShellExporter;
+
+impl ShellExporter {
+    fn is_valid_function_name(name: &str) -> bool {
+        !name.is_empty()
+            && !name.starts_with('-')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+    }
+
+    fn function_body(id: &str, shell: ShellKind) -> String {
+        let args = match shell {
+            ShellKind::Fish => "$argv",
+            ShellKind::Bash | ShellKind::Zsh => "\"$@\"",
+        };
+
+        format!("aliasx run {} -- {}", id, args)
+    }
+
+    fn function(name: &str, body: &str, shell: ShellKind) -> String {
+        match shell {
+            ShellKind::Fish => format!("function {}\n    {}\nend\n", name, body),
+            ShellKind::Bash | ShellKind::Zsh => format!("{}() {{\n    {}\n}}\n", name, body),
+        }
+    }
+
+    pub fn generate(tasks: &TaskCollection, shell: ShellKind) -> String {
+        let mut seen: IndexSet<&str> = IndexSet::new();
+        let mut out = format!(
+            "# generated by aliasx - source this file from your shell config\n\
+             # regenerate with: aliasx export --shell {}\n",
+            shell
+        );
+
+        for itask in tasks.indexed_tasks() {
+            let Some(id) = itask.task.id.as_deref() else {
+                continue;
+            };
+
+            // first definition wins - same as `aliasx run <id>`
+            if !seen.insert(id) {
+                continue;
+            }
+
+            out.push('\n');
+
+            if !Self::is_valid_function_name(id) {
+                out.push_str(&format!("# skipped '{}': not a valid function name\n", id));
+                continue;
+            }
+
+            out.push_str(&format!("# {}\n", itask.task.label));
+            let body = Self::function_body(id, shell);
+            out.push_str(&Self::function(id, &body, shell));
+        }
+
+        out
+    }
+
+    pub fn print(tasks: &TaskCollection, shell: ShellKind) {
+        print!("{}", Self::generate(tasks, shell));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::Input;
+    use crate::tasks::{TaskEntry, Tasks};
+
+    fn create_test_collection() -> TaskCollection {
+        let mut tasks = Tasks::default();
+        for (label, command, id) in [
+            ("Status", "git status", Some("gs")),
+            ("Deploy", "deploy ${input:env}", Some("deploy")),
+            ("No id", "echo no id", None),
+            ("Bad id", "echo bad", Some("bad id")),
+            ("Duplicate", "echo dup", Some("gs")),
+        ] {
+            tasks.tasks.insert(TaskEntry {
+                label: label.to_string(),
+                command: command.to_string(),
+                id: id.map(str::to_string),
+                conditions: None,
+                line: None,
+            });
+        }
+        tasks.inputs.push(Input {
+            id: "env".to_string(),
+            options: vec!["dev".into()],
+            ..Default::default()
+        });
+
+        TaskCollection::new(vec![tasks])
+    }
+
+    #[test]
+    fn test_generate_bash() {
+        let out = ShellExporter::generate(&create_test_collection(), ShellKind::Bash);
+
+        assert!(out.contains("# Status\ngs() {\n    aliasx run gs -- \"$@\"\n}\n"));
+        assert!(out.contains("# Deploy\ndeploy() {\n    aliasx run deploy -- \"$@\"\n}\n"));
+        assert!(out.contains("# skipped 'bad id': not a valid function name\n"));
+        assert!(!out.contains("echo no id"));
+        assert!(!out.contains("echo dup"));
+    }
+
+    #[test]
+    fn test_generate_fish() {
+        let out = ShellExporter::generate(&create_test_collection(), ShellKind::Fish);
+
+        assert!(out.contains("function gs\n    aliasx run gs -- $argv\nend\n"));
+        assert!(out.contains("function deploy\n    aliasx run deploy -- $argv\nend\n"));
+    }
+
+    #[test]
+    fn test_generate_id_is_command() {
+        let mut tasks = Tasks::default();
+        tasks.tasks.insert(TaskEntry {
+            label: "Make".to_string(),
+            command: "make -j8".to_string(),
+            id: Some("make".to_string()),
+            conditions: None,
+            line: None,
+        });
+        let collection = TaskCollection::new(vec![tasks]);
+
+        // inlining `make -j8` would make the function call itself
+        let out = ShellExporter::generate(&collection, ShellKind::Bash);
+        assert!(out.contains("make() {\n    aliasx run make -- \"$@\"\n}\n"));
+        assert!(!out.contains("make -j8"));
+    }
+
+    #[test]
+    fn test_valid_function_name() {
+        assert!(ShellExporter::is_valid_function_name("build-release"));
+        assert!(ShellExporter::is_valid_function_name("task_0"));
+        assert!(!ShellExporter::is_valid_function_name("-flag"));
+        assert!(!ShellExporter::is_valid_function_name("has space"));
+        assert!(!ShellExporter::is_valid_function_name("semi;colon"));
+        assert!(!ShellExporter::is_valid_function_name(""));
+    }
+}
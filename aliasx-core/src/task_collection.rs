@@ -1,7 +1,8 @@
 use anyhow::{anyhow, Context};
 use execute::shell;
 use indexmap::{IndexMap, IndexSet};
-use std::process::Stdio;
+use std::path::Path;
+use std::process::{Command, Stdio};
 
 use crate::history::HistoryEntry;
 
@@ -17,6 +18,14 @@ pub struct TaskCollection {
     sources: Vec<Tasks>,
 }
 
+/// A task command ready to run - secrets are redacted in `logged_command` and the
+/// `secret_env` inputs are passed in `envs`.
+struct PreparedCommand {
+    command: String,
+    logged_command: String,
+    envs: Vec<(String, String)>,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct IndexedTask<'a> {
     pub idx: usize,
@@ -102,13 +111,16 @@ impl TaskCollection {
     pub fn required_inputs_for_task(&self, idx: usize) -> anyhow::Result<Vec<&Input>> {
         let itask = self.find_itask_from_idx(idx)?;
 
-        return itask
+        itask
             .source
-            .required_inputs_for_command(&itask.task.command);
+            .required_inputs_for_command(&itask.task.command)
     }
 
-    pub fn validate_all(&self, verbose: bool) {
-        let validator = Validator { verbose };
+    pub fn validate_all(&self, verbose: bool, run_commands: bool) {
+        let validator = Validator {
+            verbose,
+            run_commands,
+        };
         let mut task_reports = Vec::new();
         let itasks: Vec<IndexedTask<'_>> = self.all_itasks().collect();
 
@@ -134,8 +146,11 @@ impl TaskCollection {
         );
     }
 
-    pub fn validate_at(&self, idx: usize, verbose: bool) -> anyhow::Result<()> {
-        let validator = Validator { verbose };
+    pub fn validate_at(&self, idx: usize, verbose: bool, run_commands: bool) -> anyhow::Result<()> {
+        let validator = Validator {
+            verbose,
+            run_commands,
+        };
         let itask = self.find_itask_from_idx(idx)?;
 
         let report = validator.validate_task_command(itask.task, itask.source);
@@ -162,49 +177,155 @@ impl TaskCollection {
         Ok(())
     }
 
-    /// Execute task `idx` with pre-collected `input_selections`.
-    pub fn execute(
-        &self,
+    /// Substitutes the inputs and mappings and appends `args`.
+    fn prepare_command(
         itask: &IndexedTask,
         input_selections: &IndexMap<String, String>,
-        verbose: bool,
-    ) -> anyhow::Result<()> {
+        args: &[String],
+    ) -> anyhow::Result<PreparedCommand> {
         let mut task_command = itask.task.command.clone();
+        let mut logged_command = itask.task.command.clone();
+        let mut envs = Vec::new();
 
         for input_id in Input::extract_variables(&itask.task.command) {
             let val = input_selections
                 .get(&input_id)
                 .ok_or_else(|| anyhow!("no selection provided for input '{}'", input_id))?;
-            task_command = Input::replace_next_variable(&task_command, val);
+
+            let input = itask.source.get_input(&input_id).ok();
+            if let Some(var) = input.and_then(|input| input.secret_env.as_deref()) {
+                let reference = format!("${{{}}}", var);
+                task_command = Input::replace_next_variable(&task_command, &reference);
+                logged_command = Input::replace_next_variable(&logged_command, &reference);
+                envs.push((var.to_string(), val.clone()));
+            } else {
+                let shown = input.map_or(val.as_str(), |input| input.shown(val));
+                task_command = Input::replace_next_variable(&task_command, val);
+                logged_command = Input::replace_next_variable(&logged_command, shown);
+            }
         }
 
         task_command = itask
             .source
             .apply_mappings(&task_command, input_selections)?;
+        logged_command = itask.source.redact_mappings(&logged_command)?;
+        logged_command = itask
+            .source
+            .apply_mappings(&logged_command, input_selections)?;
+
+        for arg in args {
+            task_command.push(' ');
+            task_command.push_str(&Self::quote_arg(arg));
+            logged_command.push(' ');
+            logged_command.push_str(&Self::quote_arg(arg));
+        }
+
+        Ok(PreparedCommand {
+            command: task_command,
+            logged_command,
+            envs,
+        })
+    }
 
-        let res = Self::run_command(&itask.task.format(verbose), &task_command);
+    /// Execute task `idx` with pre-collected `input_selections`.
+    /// `args` are quoted and appended to the command.
+    pub fn execute(
+        &self,
+        itask: &IndexedTask,
+        input_selections: &IndexMap<String, String>,
+        args: &[String],
+        verbose: bool,
+    ) -> anyhow::Result<()> {
+        let prepared = Self::prepare_command(itask, input_selections, args)?;
+        let res = Self::run_command(
+            &itask.task.format(verbose),
+            &prepared.command,
+            &prepared.envs,
+        );
 
         let entry = HistoryEntry::new(
             &itask.task.label,
-            &task_command,
+            &prepared.logged_command,
             if res.is_ok() { 0 } else { 1 },
             itask.source.scope,
+            &itask.source.redact_selections(input_selections),
         );
 
         if let Err(err) = History::append(&entry) {
             if verbose {
-                println!("Error while adding history: {}", err.to_string());
+                println!("Error while adding history: {}", err);
+            }
+        }
+
+        let remembered: IndexMap<String, String> = input_selections
+            .iter()
+            .filter(|(id, _)| !itask.source.is_secret_input(id))
+            .map(|(id, value)| (id.clone(), value.clone()))
+            .collect();
+
+        if let Err(err) = History::remember_selections(itask.task.selection_key(), &remembered) {
+            if verbose {
+                println!("Error while storing the selections: {}", err);
             }
         }
 
         res
     }
 
-    pub fn run_command(label: &str, task_command: &str) -> anyhow::Result<()> {
+    /// Single quotes `arg` so it is passed verbatim by bash, zsh and fish.
+    pub(crate) fn quote_arg(arg: &str) -> String {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+
+    fn editor_command(editor: &str, path: &Path, line: Option<usize>) -> Command {
+        let mut parts = editor.split_whitespace();
+        let mut cmd = Command::new(parts.next().unwrap_or("vi"));
+        cmd.args(parts);
+
+        if let Some(line) = line {
+            cmd.arg(format!("+{}", line));
+        }
+
+        cmd.arg(path);
+        cmd
+    }
+
+    /// Opens the config file defining `itask` in `$EDITOR` (fallback `vi`) at the task's line.
+    pub fn edit(itask: &IndexedTask) -> anyhow::Result<()> {
+        let path = itask.source.path.as_deref().ok_or_else(|| {
+            anyhow!(
+                "task '{}' is not defined in a config file (scope={})",
+                itask.task.label,
+                itask.source.scope
+            )
+        })?;
+
+        let editor = std::env::var("EDITOR")
+            .ok()
+            .filter(|e| !e.trim().is_empty())
+            .unwrap_or_else(|| "vi".to_string());
+
+        let status = Self::editor_command(&editor, path, itask.task.line)
+            .status()
+            .with_context(|| format!("failed to start editor '{}'", editor))?;
+
+        if !status.success() {
+            return Err(anyhow!("editor '{}' exited with non-zero status", editor));
+        }
+
+        Ok(())
+    }
+
+    pub fn run_command(
+        label: &str,
+        task_command: &str,
+        envs: &[(String, String)],
+    ) -> anyhow::Result<()> {
         println!("aliasx | {}\n", label);
 
-        let mut cmd = shell(&task_command);
-        cmd.stdin(Stdio::inherit())
+        let mut cmd = shell(task_command);
+        cmd.envs(envs.iter().map(|(var, value)| (var, value)))
+            .stdin(Stdio::inherit())
             .stdout(Stdio::inherit())
             .stderr(Stdio::inherit());
 
@@ -233,8 +354,9 @@ mod tests {
         TaskEntry {
             label: label.to_string(),
             command: command.to_string(),
-            id: id,
+            id,
             conditions: Option::None,
+            line: Option::None,
         }
     }
 
@@ -469,16 +591,14 @@ mod tests {
     fn test_find_task_with_correct_source() {
         let input1 = Input {
             id: "env1".to_string(),
-            options: vec!["dev".to_string()],
-            description: None,
-            default: None,
+            options: vec!["dev".into()],
+            ..Default::default()
         };
 
         let input2 = Input {
             id: "env2".to_string(),
-            options: vec!["prod".to_string()],
-            description: None,
-            default: None,
+            options: vec!["prod".into()],
+            ..Default::default()
         };
 
         let source1 =
@@ -500,6 +620,95 @@ mod tests {
         assert!(itask.source.get_input("env2").is_ok());
     }
 
+    #[test]
+    fn test_prepare_command_secrets() {
+        let free_text = |id: &str| Input {
+            id: id.to_string(),
+            ..Default::default()
+        };
+        let mut password = free_text("password");
+        password.secret = true;
+        let mut token = free_text("token");
+        token.secret_env = Some("API_TOKEN".to_string());
+
+        let source = create_test_tasks_with_inputs(
+            vec![(
+                "login",
+                "login ${input:user} ${input:password} --token ${input:token}",
+            )],
+            vec![free_text("user"), password, token],
+        );
+        let collection = TaskCollection::new(vec![source]);
+        let itask = collection.find_itask_from_idx(0).unwrap();
+
+        let selections = IndexMap::from([
+            ("user".to_string(), "me".to_string()),
+            ("password".to_string(), "hunter2".to_string()),
+            ("token".to_string(), "abc".to_string()),
+        ]);
+        let prepared =
+            TaskCollection::prepare_command(&itask, &selections, &["-v".to_string()]).unwrap();
+
+        assert_eq!(
+            prepared.command,
+            "login me hunter2 --token ${API_TOKEN} '-v'"
+        );
+        assert_eq!(
+            prepared.logged_command,
+            "login me *** --token ${API_TOKEN} '-v'"
+        );
+        assert_eq!(
+            prepared.envs,
+            vec![("API_TOKEN".to_string(), "abc".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_prepare_command_secret_mapping() {
+        let password: Input = serde_yaml::from_str("{id: password, secret: true}").unwrap();
+        let mut source = create_test_tasks_with_inputs(
+            vec![("login", "login --password=${mapping:password-arg}")],
+            vec![password],
+        );
+        source.mappings = serde_yaml::from_str(
+            "[{id: password-arg, input: password, passthrough: true, options: {}}]",
+        )
+        .unwrap();
+        let collection = TaskCollection::new(vec![source]);
+        let itask = collection.find_itask_from_idx(0).unwrap();
+
+        let selections = IndexMap::from([("password".to_string(), "hunter2".to_string())]);
+        let prepared = TaskCollection::prepare_command(&itask, &selections, &[]).unwrap();
+
+        assert_eq!(prepared.command, "login --password=hunter2");
+        assert_eq!(prepared.logged_command, "login --password=***");
+    }
+
+    #[test]
+    fn test_quote_arg() {
+        assert_eq!(TaskCollection::quote_arg("simple"), "'simple'");
+        assert_eq!(TaskCollection::quote_arg("with space"), "'with space'");
+        assert_eq!(TaskCollection::quote_arg("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn test_editor_command() {
+        let path = Path::new("/tmp/.aliasx.yaml");
+
+        let cmd = TaskCollection::editor_command("code --wait", path, Some(12));
+        assert_eq!(cmd.get_program(), "code");
+        assert_eq!(
+            cmd.get_args().collect::<Vec<_>>(),
+            vec!["--wait", "+12", "/tmp/.aliasx.yaml"]
+        );
+
+        let cmd = TaskCollection::editor_command("vim", path, None);
+        assert_eq!(
+            cmd.get_args().collect::<Vec<_>>(),
+            vec!["/tmp/.aliasx.yaml"]
+        );
+    }
+
     #[test]
     fn test_task_idx_deduplication() {
         let source1 = create_test_tasks(vec![("task1", "echo1"), ("task2", "echo2")]);
@@ -1,12 +1,15 @@
 use globset::{Glob, GlobSetBuilder};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{env, fs};
 
-#[derive(Hash, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Hash, Eq, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct TaskCondition {
+    /// globs matched against the current directory
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub paths: Vec<String>,
 
+    /// globs matched against the files in the current directory
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub files: Vec<String>,
 }
@@ -79,13 +82,13 @@ impl TaskCondition {
     // for simplicity - just return the first error if any
     pub fn validate(&self) -> Option<String> {
         for file in self.files.iter() {
-            if let Err(_) = Glob::new(file) {
+            if Glob::new(file).is_err() {
                 return Some(format!("files: {}", file.clone()));
             }
         }
 
         for path in self.paths.iter() {
-            if let Err(_) = Glob::new(path) {
+            if Glob::new(path).is_err() {
                 return Some(format!("paths: {}", path.clone()));
             }
         }
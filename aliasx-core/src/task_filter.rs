@@ -1,18 +1,15 @@
 use strum::{Display, EnumString};
 use serde::{Deserialize, Serialize};
 
-#[derive(EnumString, Display, Deserialize, Serialize, Debug, PartialEq, Clone, Copy)]
+#[derive(EnumString, Display, Deserialize, Serialize, Debug, PartialEq, Clone, Copy, Default)]
 #[strum(serialize_all = "lowercase")]
 pub enum TaskFilter {
     All,
+    #[default]
     Local,
     Global,
-}
-
-impl Default for TaskFilter {
-    fn default() -> Self {
-        TaskFilter::Local
-    }
+    Native,
+    Git,
 }
 
 impl TaskFilter {
@@ -23,6 +20,14 @@ impl TaskFilter {
     pub fn include_global(self) -> bool {
         matches!(self, TaskFilter::All | TaskFilter::Global)
     }
+
+    pub fn include_native(self) -> bool {
+        matches!(self, TaskFilter::All | TaskFilter::Native)
+    }
+
+    pub fn include_git(self) -> bool {
+        matches!(self, TaskFilter::All | TaskFilter::Git)
+    }
 }
 
 #[cfg(test)]
@@ -34,6 +39,7 @@ mod tests {
         assert!(TaskFilter::All.include_local());
         assert!(TaskFilter::Local.include_local());
         assert!(!TaskFilter::Global.include_local());
+        assert!(!TaskFilter::Native.include_local());
     }
 
     #[test]
@@ -41,5 +47,23 @@ mod tests {
         assert!(TaskFilter::All.include_global());
         assert!(!TaskFilter::Local.include_global());
         assert!(TaskFilter::Global.include_global());
+        assert!(!TaskFilter::Native.include_global());
+    }
+
+    #[test]
+    fn test_task_filter_include_native() {
+        assert!(TaskFilter::All.include_native());
+        assert!(!TaskFilter::Local.include_native());
+        assert!(!TaskFilter::Global.include_native());
+        assert!(TaskFilter::Native.include_native());
+        assert!(!TaskFilter::Git.include_native());
+    }
+
+    #[test]
+    fn test_task_filter_include_git() {
+        assert!(TaskFilter::All.include_git());
+        assert!(!TaskFilter::Local.include_git());
+        assert!(!TaskFilter::Native.include_git());
+        assert!(TaskFilter::Git.include_git());
     }
 }
@@ -1,7 +1,11 @@
 use anyhow::Context;
 use std::path::Path;
 
-use crate::{task_filter::TaskFilter, tasks::Tasks};
+use crate::{
+    config_document::{ConfigDocument, Node},
+    task_filter::TaskFilter,
+    tasks::Tasks,
+};
 
 /// strict parsing - will fail if not exists or if malformed
 pub fn parse_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Tasks> {
@@ -10,7 +14,44 @@ pub fn parse_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Tasks> {
     let format = TaskFormat::from_path(path)
         .with_context(|| format!("unsupported file format: {:?}", path))?;
 
-    format.parse(path)
+    let mut tasks = format.parse(path)?;
+    tasks.path = Some(path.to_path_buf());
+
+    if let Ok(document) = ConfigDocument::load(path) {
+        locate_tasks(&document, &mut tasks);
+    }
+
+    Ok(tasks)
+}
+
+/// Sets the line of each task to the line of its `label` in `document`.
+/// Tasks are searched in order, so duplicated labels resolve to consecutive definitions.
+fn locate_tasks(document: &ConfigDocument, tasks: &mut Tasks) {
+    let Ok(root) = document.root() else {
+        return;
+    };
+    let Some(items) = root.get("tasks").and_then(Node::items) else {
+        return;
+    };
+    let mut start = 0;
+
+    tasks.tasks = std::mem::take(&mut tasks.tasks)
+        .into_iter()
+        .map(|mut task| {
+            let found = items[start.min(items.len())..].iter().position(|item| {
+                item.get("label").and_then(Node::as_str) == Some(task.label.as_str())
+                    && item.get("command").and_then(Node::as_str) == Some(task.command.as_str())
+            });
+
+            if let Some(offset) = found {
+                let item = &items[start + offset];
+                task.line = Some(item.get("label").map_or(item.line, |label| label.line));
+                start += offset + 1;
+            }
+
+            task
+        })
+        .collect();
 }
 
 pub fn push_if_exists<P>(sources: &mut Vec<Tasks>, path: P, filter: TaskFilter) -> anyhow::Result<()>
@@ -31,13 +72,14 @@ where
     Ok(())
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TaskFormat {
     Yaml,
     Json,
 }
 
 impl TaskFormat {
-    fn from_path(path: &Path) -> Option<Self> {
+    pub fn from_path(path: &Path) -> Option<Self> {
         match path.extension().and_then(|e| e.to_str()) {
             Some("yaml") | Some("yml") => Some(Self::Yaml),
             Some("json") | Some("json5") => Some(Self::Json),
@@ -67,8 +109,8 @@ impl TaskReader for YamlTaskReader {
 
         let reader = std::io::BufReader::new(file);
 
-        Ok(serde_yaml::from_reader(reader)
-            .with_context(|| format!("failed to parse YAML: {:?}", path.as_ref()))?)
+        serde_yaml::from_reader(reader)
+            .with_context(|| format!("failed to parse YAML: {:?}", path.as_ref()))
     }
 }
 
@@ -79,7 +121,70 @@ impl TaskReader for JsonTaskReader {
 
         let reader = std::io::BufReader::new(file);
 
-        Ok(serde_json5::from_reader(reader)
-            .with_context(|| format!("failed to parse JSON: {:?}", path.as_ref()))?)
+        serde_json5::from_reader(reader)
+            .with_context(|| format!("failed to parse JSON: {:?}", path.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_tasks(labels: &[&str]) -> Tasks {
+        let mut tasks = Tasks::default();
+        for label in labels {
+            tasks.tasks.insert(crate::tasks::TaskEntry {
+                label: label.to_string(),
+                command: format!("echo {}", label),
+                id: None,
+                conditions: None,
+                line: None,
+            });
+        }
+        tasks
+    }
+
+    fn lines(tasks: &Tasks) -> Vec<Option<usize>> {
+        tasks.tasks.iter().map(|t| t.line).collect()
+    }
+
+    fn locate(content: &str, format: TaskFormat, labels: &[&str]) -> Vec<Option<usize>> {
+        let mut tasks = create_test_tasks(labels);
+        locate_tasks(&ConfigDocument::new(content, format), &mut tasks);
+        lines(&tasks)
+    }
+
+    #[test]
+    fn test_locate_tasks_yaml() {
+        let content = "# comment\ntasks:\n  - label: Build\n    command: echo Build\n\n  - command: echo Test\n    label: \"Test\"\n";
+
+        assert_eq!(
+            locate(content, TaskFormat::Yaml, &["Build", "Test"]),
+            vec![Some(3), Some(7)]
+        );
+    }
+
+    #[test]
+    fn test_locate_tasks_json() {
+        let content = "{\n  // \"label\": \"Build release\"\n  \"tasks\": [\n    { \"label\": \"Build\", \"command\": \"echo Build\" },\n    {\n      \"label\": \"Build release\",\n      \"command\": \"echo Build release\"\n    }\n  ]\n}\n";
+
+        assert_eq!(
+            locate(content, TaskFormat::Json, &["Build", "Build release"]),
+            vec![Some(4), Some(6)]
+        );
+    }
+
+    #[test]
+    fn test_locate_tasks_quoted() {
+        let content = "tasks:\n  # label: Other\n  - label: 'It''s quoted'\n    command: echo It's quoted\n  - label: \"Other\"\n    command: echo Other\n";
+
+        assert_eq!(
+            locate(
+                content,
+                TaskFormat::Yaml,
+                &["It's quoted", "Other", "Missing"]
+            ),
+            vec![Some(3), Some(5), None]
+        );
     }
 }
@@ -1,27 +1,42 @@
 use anyhow::{anyhow, Context};
 use indexmap::{IndexMap, IndexSet};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 
-use crate::input::Input;
+use crate::aliases::{self, NativeOptions};
+use crate::git_aliases;
+use crate::input::{Input, REDACTED};
 use crate::input_mapping::InputMapping;
 use crate::task_collection::TaskCollection;
 use crate::task_conditions::TaskCondition;
 use crate::task_filter::TaskFilter;
 use crate::task_reader;
 
-#[derive(Hash, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct TaskEntry {
+    /// name shown in the task list
     pub label: String,
+
+    /// shell command, may reference `${input:<id>}` and `${mapping:<id>}`
     pub command: String,
 
+    /// used to run the task with `aliasx run <id>`
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
 
+    /// only show the task if one of the conditions matches
     #[serde(skip_serializing_if = "Option::is_none")]
     pub conditions: Option<TaskCondition>,
+
+    /// 1-based line of the task in its config file, see [`Tasks::path`]
+    #[serde(skip)]
+    pub line: Option<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default, JsonSchema)]
+#[schemars(title = "aliasx config")]
 pub struct Tasks {
     pub version: Option<String>,
     pub tasks: IndexSet<TaskEntry>,
@@ -34,14 +49,44 @@ pub struct Tasks {
 
     #[serde(skip)]
     pub scope: TaskFilter,
+
+    /// config file the tasks were read from, `None` for aliases
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+// the line is left out so the same task defined in several configs is still deduplicated
+impl PartialEq for TaskEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.label == other.label
+            && self.command == other.command
+            && self.id == other.id
+            && self.conditions == other.conditions
+    }
+}
+
+impl Eq for TaskEntry {}
+
+impl Hash for TaskEntry {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.label.hash(state);
+        self.command.hash(state);
+        self.id.hash(state);
+        self.conditions.hash(state);
+    }
 }
 
 impl TaskEntry {
+    /// Identifies the task in the last selections - the id or else the label.
+    pub fn selection_key(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.label)
+    }
+
     pub fn format(&self, verbose: bool) -> String {
         if verbose {
             format!("{} -> {}", self.label, self.command)
         } else {
-            format!("{}", self.label)
+            self.label.to_string()
         }
     }
 
@@ -61,6 +106,16 @@ impl Tasks {
         });
     }
 
+    /// Directory of the config file - relative paths in the config are resolved from here.
+    pub fn config_dir(&self) -> PathBuf {
+        self.path
+            .as_deref()
+            .and_then(Path::parent)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
     pub fn get_input(&self, id: &str) -> anyhow::Result<&Input> {
         if self.inputs.is_empty() {
             return Err(anyhow!("no inputs defined"));
@@ -83,6 +138,18 @@ impl Tasks {
             .ok_or_else(|| anyhow!("mapping with id '{}' not found", id))
     }
 
+    /// The mapping and field referenced by `${mapping:<reference>}` - either `<id>` or `<id>.<field>`.
+    pub fn resolve_mapping<'a>(
+        &self,
+        reference: &'a str,
+    ) -> anyhow::Result<(&InputMapping, Option<&'a str>)> {
+        match (self.get_mapping(reference), reference.rsplit_once('.')) {
+            (Ok(mapping), _) => Ok((mapping, None)),
+            (Err(_), Some((id, field))) => Ok((self.get_mapping(id)?, Some(field))),
+            (Err(err), None) => Err(err),
+        }
+    }
+
     /// Returns all inputs required to execute `command`, including those
     /// referenced indirectly through mappings.
     pub fn required_inputs_for_command(&self, command: &str) -> anyhow::Result<Vec<&Input>> {
@@ -93,11 +160,202 @@ impl Tasks {
         }
 
         for map_id in InputMapping::extract_from_str(command) {
-            let mapping = self.get_mapping(&map_id)?;
-            ids.insert(mapping.input.clone());
+            let (mapping, _) = self.resolve_mapping(&map_id)?;
+            ids.extend(mapping.input_ids().into_iter().map(String::from));
+        }
+
+        self.ordered_inputs(ids)
+    }
+
+    /// Definitions `command` needs that `target` doesn't have, copied from this config: the
+    /// mappings it references and the inputs the command and those mappings depend on.
+    /// Inputs this config doesn't define either are left out.
+    pub fn definitions_missing_in(
+        &self,
+        command: &str,
+        target: &Tasks,
+    ) -> anyhow::Result<(Vec<Input>, Vec<InputMapping>)> {
+        let mut mappings: Vec<InputMapping> = Vec::new();
+        for map_id in InputMapping::extract_from_str(command) {
+            if target.resolve_mapping(&map_id).is_ok() {
+                continue;
+            }
+
+            let (mapping, _) = self
+                .resolve_mapping(&map_id)
+                .with_context(|| format!("mapping '{}' can't be copied", map_id))?;
+            if !mappings.iter().any(|m| m.id == mapping.id) {
+                mappings.push(mapping.clone());
+            }
+        }
+
+        let mut ids: IndexSet<String> = Input::extract_variables(command).into_iter().collect();
+        for mapping in &mappings {
+            ids.extend(mapping.input_ids().into_iter().map(String::from));
         }
+        ids.retain(|id| self.get_input(id).is_ok());
+
+        let inputs = self
+            .ordered_inputs(ids)?
+            .into_iter()
+            .filter(|input| target.get_input(&input.id).is_err())
+            .cloned()
+            .collect();
 
-        ids.iter().map(|id| self.get_input(id)).collect()
+        Ok((inputs, mappings))
+    }
+
+    /// The inputs for `ids` and the inputs their `options_by` depends on, ordered so that
+    /// every input comes after the inputs it depends on.
+    pub fn ordered_inputs(
+        &self,
+        ids: impl IntoIterator<Item = String>,
+    ) -> anyhow::Result<Vec<&Input>> {
+        let mut ordered = Vec::new();
+        let mut path = Vec::new();
+
+        for id in ids {
+            self.visit_input(&id, &mut path, &mut ordered)?;
+        }
+
+        Ok(ordered)
+    }
+
+    fn visit_input<'a>(
+        &'a self,
+        id: &str,
+        path: &mut Vec<String>,
+        ordered: &mut Vec<&'a Input>,
+    ) -> anyhow::Result<()> {
+        if ordered.iter().any(|input| input.id == id) {
+            return Ok(());
+        }
+
+        if let Some(start) = path.iter().position(|visited| visited == id) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(id.to_string());
+            return Err(anyhow!(
+                "inputs depend on each other: {}",
+                cycle.join(" -> ")
+            ));
+        }
+
+        let input = self.get_input(id)?;
+
+        if let Some(by) = &input.options_by {
+            path.push(id.to_string());
+            self.visit_input(&by.input, path, ordered)?;
+            path.pop();
+        }
+
+        ordered.push(input);
+        Ok(())
+    }
+
+    /// Values given up front for the inputs of `command` - `values` (eg. from `--input id=value`)
+    /// take precedence over `ALIASX_INPUT_<ID>` environment variables. Values are checked
+    /// against the input's options.
+    pub fn preset_selections(
+        &self,
+        command: &str,
+        values: &[(String, String)],
+    ) -> anyhow::Result<IndexMap<String, String>> {
+        self.preset_selections_with(command, values, |var| std::env::var(var).ok())
+    }
+
+    fn preset_selections_with(
+        &self,
+        command: &str,
+        values: &[(String, String)],
+        env: impl Fn(&str) -> Option<String>,
+    ) -> anyhow::Result<IndexMap<String, String>> {
+        let inputs = self.required_inputs_for_command(command)?;
+
+        if let Some((id, _)) = values
+            .iter()
+            .find(|(id, _)| !inputs.iter().any(|i| &i.id == id))
+        {
+            return Err(anyhow!("the task doesn't use input '{}'", id));
+        }
+
+        let mut selections = IndexMap::new();
+
+        for input in inputs {
+            let value = values
+                .iter()
+                .rev()
+                .find(|(id, _)| id == &input.id)
+                .map(|(_, value)| value.clone())
+                .or_else(|| env(&input.env_var()));
+
+            let Some(value) = value else {
+                continue;
+            };
+
+            self.check_selection(input, &value, &selections)?;
+            selections.insert(input.id.clone(), value);
+        }
+
+        Ok(selections)
+    }
+
+    /// Checks `value` against the options `input` has for the earlier `selections` and
+    /// its rules. Options are only resolved when the input doesn't accept custom values.
+    pub fn check_selection(
+        &self,
+        input: &Input,
+        value: &str,
+        selections: &IndexMap<String, String>,
+    ) -> anyhow::Result<()> {
+        let input = input.select_options_by(selections)?;
+        if input.accepts_custom() {
+            input.check_value(value)
+        } else {
+            input
+                .resolve_options(&self.config_dir())?
+                .check_value(value)
+        }
+    }
+
+    pub fn is_secret_input(&self, id: &str) -> bool {
+        self.get_input(id).is_ok_and(Input::is_secret)
+    }
+
+    /// `selections` with the values of secret inputs replaced by [`REDACTED`].
+    pub fn redact_selections(
+        &self,
+        selections: &IndexMap<String, String>,
+    ) -> IndexMap<String, String> {
+        selections
+            .iter()
+            .map(|(id, value)| {
+                let value = if self.is_secret_input(id) {
+                    REDACTED.to_string()
+                } else {
+                    value.clone()
+                };
+                (id.clone(), value)
+            })
+            .collect()
+    }
+
+    /// `command` with the mappings over secret inputs replaced by [`REDACTED`] - a mapped
+    /// value, especially a `passthrough`, would show the secret.
+    pub fn redact_mappings(&self, command: &str) -> anyhow::Result<String> {
+        let mut redacted = command.to_string();
+
+        for map_str in InputMapping::extract_from_str(command) {
+            let (input_mapping, _) = self.resolve_mapping(&map_str)?;
+            if input_mapping
+                .input_ids()
+                .into_iter()
+                .any(|id| self.is_secret_input(id))
+            {
+                redacted = InputMapping::replace_all(&redacted, &map_str, REDACTED)?;
+            }
+        }
+
+        Ok(redacted)
     }
 
     pub fn apply_mappings(
@@ -109,44 +367,69 @@ impl Tasks {
         let mut mapped_str = command.to_string();
 
         for map_str in mapping_strings {
-            let input_mapping = self.get_mapping(&map_str)?;
-            let key = &input_mapping.input;
-
-            let sel_value = input_selections
-                .get(key)
-                .ok_or_else(|| anyhow!("no selection found for input '{}'", key))?;
-
-            let mapping = input_mapping.options.get(sel_value).ok_or_else(|| {
-                anyhow!(
-                    "no mapping found for selection '{}' in input '{}'",
-                    sel_value,
-                    key
-                )
-            })?;
-
-            mapped_str = InputMapping::replace_all(&mapped_str, &input_mapping.id, mapping)?;
+            let (input_mapping, field) = self.resolve_mapping(&map_str)?;
+            let input_ids = input_mapping.input_ids();
+            let key = &InputMapping::key(&input_ids);
+
+            let sel_values = input_ids
+                .iter()
+                .map(|id| {
+                    input_selections
+                        .get(*id)
+                        .map(String::as_str)
+                        .ok_or_else(|| anyhow!("no selection found for input '{}'", id))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let sel_value = &InputMapping::key(&sel_values);
+
+            let map_value = |value: &str| input_mapping.map_value(value, field);
+
+            // selections of `multiple` inputs are mapped one by one and joined again,
+            // composite mappings always map the combined selection
+            let mapping = match self.get_input(key) {
+                Ok(input) if input.multiple && !input_mapping.is_composite() => input.join_values(
+                    &input
+                        .split_value(sel_value)
+                        .into_iter()
+                        .map(map_value)
+                        .collect::<anyhow::Result<Vec<_>>>()?,
+                ),
+                _ => map_value(sel_value)?,
+            };
+
+            mapped_str = InputMapping::replace_all(&mapped_str, &map_str, &mapping)?;
         }
 
         Ok(mapped_str)
     }
 }
 
-pub fn get_all_tasks(filter: TaskFilter, apply_conditions: bool) -> anyhow::Result<TaskCollection> {
-    const LOCAL_SOURCES: &[&str] = &[
-        ".aliasx.yaml",
-        ".aliasx.yml",
-        ".aliasx.json",
-        ".aliasx.json5",
-        ".vscode/tasks.json",
-    ];
-
-    const GLOBAL_SOURCES: &[&str] = &[
-        ".aliasx.yaml",
-        ".aliasx.yml",
-        ".aliasx.json",
-        ".aliasx.json5",
-    ];
-
+/// Config files searched in the current directory (in order of precedence).
+pub const LOCAL_SOURCES: &[&str] = &[
+    ".aliasx.yaml",
+    ".aliasx.yml",
+    ".aliasx.json",
+    ".aliasx.json5",
+    ".vscode/tasks.json",
+];
+
+/// Config files searched in the home directory (in order of precedence).
+pub const GLOBAL_SOURCES: &[&str] = &[
+    ".aliasx.yaml",
+    ".aliasx.yml",
+    ".aliasx.json",
+    ".aliasx.json5",
+];
+
+/// Collects tasks from all sources included by `filter`.
+/// Native aliases are only loaded when `native` is provided as it requires spawning a shell,
+/// git aliases only when `git` is set as it requires running git.
+pub fn get_all_tasks(
+    filter: TaskFilter,
+    apply_conditions: bool,
+    native: Option<NativeOptions>,
+    git: bool,
+) -> anyhow::Result<TaskCollection> {
     let mut sources = Vec::new();
 
     if filter.include_local() {
@@ -167,6 +450,24 @@ pub fn get_all_tasks(filter: TaskFilter, apply_conditions: bool) -> anyhow::Resu
         }
     }
 
+    if git && filter.include_git() {
+        match git_aliases::get_git_aliases() {
+            Ok(tasks) => sources.push(tasks),
+            Err(err) if filter == TaskFilter::Git => return Err(err),
+            // git might not be installed - only fail if git aliases were requested explicitly
+            Err(_) => {}
+        }
+    }
+
+    if let Some(options) = native.filter(|_| filter.include_native()) {
+        match aliases::get_aliases(options) {
+            Ok(tasks) => sources.push(tasks),
+            Err(err) if filter == TaskFilter::Native => return Err(err),
+            // native aliases are best effort when merged with the configured tasks
+            Err(_) => {}
+        }
+    }
+
     if apply_conditions {
         for source in sources.iter_mut() {
             source.apply_conditions();
@@ -179,6 +480,7 @@ pub fn get_all_tasks(filter: TaskFilter, apply_conditions: bool) -> anyhow::Resu
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::{InputOption, OptionsBy};
 
     fn create_test_task(label: &str, command: &str) -> TaskEntry {
         TaskEntry {
@@ -186,15 +488,15 @@ mod tests {
             command: command.to_string(),
             id: Some("id".to_string()),
             conditions: Option::None,
+            line: Option::None,
         }
     }
 
     fn create_test_input(id: &str, options: Vec<&str>) -> Input {
         Input {
             id: id.to_string(),
-            options: options.iter().map(|s| s.to_string()).collect(),
-            description: None,
-            default: None,
+            options: options.iter().map(|s| InputOption::from(*s)).collect(),
+            ..Default::default()
         }
     }
 
@@ -205,7 +507,10 @@ mod tests {
         let tasks = Tasks::default();
         let task = create_test_task("simple", "echo hello");
 
-        let validator = Validator { verbose: false };
+        let validator = Validator {
+            verbose: false,
+            run_commands: false,
+        };
         let report = validator.validate_task_command(&task, &tasks);
         assert!(!report.has_failures());
     }
@@ -221,11 +526,156 @@ mod tests {
 
         let task = create_test_task("deploy", "deploy ${input:env}");
 
-        let validator = Validator { verbose: false };
+        let validator = Validator {
+            verbose: false,
+            run_commands: false,
+        };
         let report = validator.validate_task_command(&task, &tasks);
         assert!(!report.has_failures());
     }
 
+    #[test]
+    fn test_validate_config_env_var_collision() {
+        use crate::validator::{ValidationStatus, Validator};
+
+        let mut tasks = Tasks::default();
+        tasks
+            .inputs
+            .push(create_test_input("build-type", vec!["debug"]));
+        tasks
+            .inputs
+            .push(create_test_input("build_type", vec!["debug"]));
+
+        let validator = Validator {
+            verbose: false,
+            run_commands: false,
+        };
+
+        let task = create_test_task("build", "build ${input:build-type}");
+        assert!(!validator
+            .validate_task_command(&task, &tasks)
+            .has_failures());
+
+        let task = create_test_task("build", "build ${input:build-type} ${input:build_type}");
+        let report = validator.validate_task_command(&task, &tasks);
+        let failures: Vec<_> = report.failures().cloned().collect();
+        assert_eq!(
+            failures,
+            vec![ValidationStatus::Fail {
+                message: "Inputs 'build-type' and 'build_type' are both preset by ALIASX_INPUT_BUILD_TYPE"
+                    .to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_config_secret_passthrough() {
+        use crate::validator::{ValidationStatus, Validator};
+
+        let mut tasks = Tasks::default();
+        let mut password = create_test_input("password", vec![]);
+        password.secret = true;
+        tasks.inputs.push(password);
+        tasks.mappings = serde_yaml::from_str(
+            "[{id: password-arg, input: password, passthrough: true, options: {}}]",
+        )
+        .unwrap();
+
+        let validator = Validator {
+            verbose: false,
+            run_commands: false,
+        };
+        let task = create_test_task("login", "login ${mapping:password-arg}");
+        let report = validator.validate_task_command(&task, &tasks);
+        let warnings: Vec<_> = report.warnings().cloned().collect();
+        assert_eq!(
+            warnings,
+            vec![ValidationStatus::Warn {
+                message: "Mapping 'password-arg' passes secret input 'password' through, the secret ends up in the command line"
+                    .to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_config_composite_mapping() {
+        use crate::validator::{ValidationStatus, Validator};
+
+        let mut tasks = Tasks::default();
+        tasks
+            .inputs
+            .push(create_test_input("env", vec!["dev", "prod/eu"]));
+        tasks.inputs.push(create_test_input("name", vec![]));
+        tasks.mappings = serde_yaml::from_str(
+            "[{id: hosts, inputs: [env, name], passthrough: true, options: {}},
+              {id: names, inputs: [name, missing], default: x, options: {}}]",
+        )
+        .unwrap();
+        assert_eq!(tasks.mappings[0].input, None);
+
+        let validator = Validator {
+            verbose: false,
+            run_commands: false,
+        };
+        let task = create_test_task("deploy", "deploy ${mapping:hosts} ${mapping:names}");
+        let report = validator.validate_task_command(&task, &tasks);
+        let failures: Vec<String> = report
+            .failures()
+            .map(|status| match status {
+                ValidationStatus::Fail { message } => message.clone(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(
+            failures,
+            vec![
+                "Mapping 'hosts' combines input 'env' with options containing '/'",
+                "Mapping 'names' references undefined input 'missing'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_config_dynamic_mapping_inputs() {
+        use crate::validator::Validator;
+
+        let mut tasks = create_cascading_tasks();
+        let mut branch = create_test_input("branch", vec![]);
+        branch.options_command = Some("printf 'main\\ndev\\n'".to_string());
+        tasks.inputs.push(branch);
+        tasks.mappings = serde_yaml::from_str(
+            "[{id: remote, input: branch, options: {main: origin}},
+              {id: cluster, inputs: [env, region], options: {dev/local: a, prod/us: b}}]",
+        )
+        .unwrap();
+        let task = create_test_task("deploy", "deploy ${mapping:remote} ${mapping:cluster}");
+
+        let messages = |run_commands: bool| -> Vec<String> {
+            let validator = Validator {
+                verbose: false,
+                run_commands,
+            };
+            validator
+                .validate_task_command(&task, &tasks)
+                .statuses
+                .iter()
+                .map(|status| status.format())
+                .collect()
+        };
+
+        // options_by options are checked per env, eg. dev/us can't be selected
+        let statuses = messages(false);
+        assert_eq!(statuses.len(), 2);
+        assert!(statuses[0].contains(
+            "Mapping 'remote' coverage of input 'branch' not checked: options_command not run"
+        ));
+        assert!(statuses[1].contains("Mapping 'cluster' doesn't define option for input 'prod/eu'"));
+
+        let statuses = messages(true);
+        assert_eq!(statuses.len(), 2);
+        assert!(statuses[0].contains("Mapping 'remote' doesn't define option for input 'dev'"));
+    }
+
     #[test]
     fn test_validate_config_missing_input() {
         use crate::validator::Validator;
@@ -233,7 +683,10 @@ mod tests {
         let tasks = Tasks::default();
         let task = create_test_task("deploy", "deploy ${input:missing}");
 
-        let validator = Validator { verbose: false };
+        let validator = Validator {
+            verbose: false,
+            run_commands: false,
+        };
         let report = validator.validate_task_command(&task, &tasks);
         assert!(report.has_failures());
     }
@@ -252,7 +705,10 @@ mod tests {
 
         let task = create_test_task("deploy", "deploy ${input:env} ${input:region}");
 
-        let validator = Validator { verbose: false };
+        let validator = Validator {
+            verbose: false,
+            run_commands: false,
+        };
         let report = validator.validate_task_command(&task, &tasks);
         assert!(!report.has_failures());
     }
@@ -268,7 +724,10 @@ mod tests {
 
         let task = create_test_task("deploy", "deploy ${input:env} ${input:missing}");
 
-        let validator = Validator { verbose: false };
+        let validator = Validator {
+            verbose: false,
+            run_commands: false,
+        };
         let report = validator.validate_task_command(&task, &tasks);
         assert!(report.has_failures());
     }
@@ -284,11 +743,448 @@ mod tests {
 
         let task = create_test_task("deploy", "deploy ${input:env} again ${input:env}");
 
-        let validator = Validator { verbose: false };
+        let validator = Validator {
+            verbose: false,
+            run_commands: false,
+        };
         let report = validator.validate_task_command(&task, &tasks);
         assert!(!report.has_failures());
     }
 
+    #[test]
+    fn test_validate_config_options_command() {
+        use crate::validator::Validator;
+
+        let mut tasks = Tasks::default();
+        let mut input = create_test_input("branch", vec![]);
+        input.options_command = Some("exit 1".to_string());
+        tasks.inputs.push(input);
+
+        let task = create_test_task("checkout", "git checkout ${input:branch}");
+
+        // commands are only run on request
+        let validator = Validator {
+            verbose: false,
+            run_commands: false,
+        };
+        assert!(!validator
+            .validate_task_command(&task, &tasks)
+            .has_failures());
+
+        let validator = Validator {
+            verbose: false,
+            run_commands: true,
+        };
+        assert!(validator
+            .validate_task_command(&task, &tasks)
+            .has_failures());
+
+        tasks.inputs[0].options_command = Some("echo main".to_string());
+        assert!(!validator
+            .validate_task_command(&task, &tasks)
+            .has_failures());
+    }
+
+    #[test]
+    fn test_validate_config_input_rules() {
+        use crate::validator::Validator;
+
+        let mut tasks = Tasks::default();
+        let mut input = create_test_input("port", vec![]);
+        input.default = Some("80".to_string());
+        input.min = Some(1024.0);
+        tasks.inputs.push(input);
+
+        let task = create_test_task("serve", "serve --port ${input:port}");
+        let validator = Validator {
+            verbose: false,
+            run_commands: false,
+        };
+        assert!(validator
+            .validate_task_command(&task, &tasks)
+            .has_failures());
+
+        tasks.inputs[0].default = Some("8080".to_string());
+        assert!(!validator
+            .validate_task_command(&task, &tasks)
+            .has_failures());
+    }
+
+    #[test]
+    fn test_validate_config_default_command() {
+        use crate::validator::Validator;
+
+        let mut tasks = Tasks::default();
+        let mut input = create_test_input("branch", vec![]);
+        input.default_command = Some("exit 1".to_string());
+        tasks.inputs.push(input);
+
+        let task = create_test_task("checkout", "git checkout ${input:branch}");
+        let validator = Validator {
+            verbose: false,
+            run_commands: false,
+        };
+        assert!(!validator
+            .validate_task_command(&task, &tasks)
+            .has_failures());
+
+        let validator = Validator {
+            verbose: false,
+            run_commands: true,
+        };
+        assert!(validator
+            .validate_task_command(&task, &tasks)
+            .has_failures());
+
+        tasks.inputs[0].default_command = Some("echo main".to_string());
+        assert!(!validator
+            .validate_task_command(&task, &tasks)
+            .has_failures());
+    }
+
+    #[test]
+    fn test_config_dir() {
+        let mut tasks = Tasks::default();
+        assert_eq!(tasks.config_dir(), PathBuf::from("."));
+
+        tasks.path = Some(PathBuf::from(".aliasx.yaml"));
+        assert_eq!(tasks.config_dir(), PathBuf::from("."));
+
+        tasks.path = Some(PathBuf::from("/home/user/.vscode/tasks.json"));
+        assert_eq!(tasks.config_dir(), PathBuf::from("/home/user/.vscode"));
+    }
+
+    #[test]
+    fn test_apply_mappings_multiple() {
+        let mut tasks = Tasks::default();
+        let mut input = create_test_input("packages", vec!["core", "cli"]);
+        input.multiple = true;
+        input.separator = Some(",".to_string());
+        tasks.inputs.push(input);
+        tasks.mappings.push(InputMapping {
+            id: "dirs".to_string(),
+            input: Some("packages".to_string()),
+            inputs: Vec::new(),
+            options: IndexMap::from([
+                ("core".to_string(), "aliasx-core".into()),
+                ("cli".to_string(), "aliasx-cli".into()),
+            ]),
+            default: None,
+            passthrough: false,
+        });
+
+        let selections = IndexMap::from([("packages".to_string(), "core,cli".to_string())]);
+        let command = tasks
+            .apply_mappings("ls ${mapping:dirs}", &selections)
+            .unwrap();
+        assert_eq!(command, "ls aliasx-core,aliasx-cli");
+
+        let selections = IndexMap::from([("packages".to_string(), "core,tui".to_string())]);
+        assert!(tasks
+            .apply_mappings("ls ${mapping:dirs}", &selections)
+            .is_err());
+    }
+
+    #[test]
+    fn test_apply_mappings_fallback() {
+        let mut tasks = Tasks::default();
+        tasks
+            .inputs
+            .push(create_test_input("profile", vec!["dev", "prod", "qa"]));
+        tasks.mappings.push(InputMapping {
+            id: "host".to_string(),
+            input: Some("profile".to_string()),
+            inputs: Vec::new(),
+            options: IndexMap::from([("prod".to_string(), "prod.example.com".into())]),
+            default: Some("localhost".into()),
+            passthrough: false,
+        });
+
+        let selections = IndexMap::from([("profile".to_string(), "qa".to_string())]);
+        let command = tasks
+            .apply_mappings("ssh ${mapping:host}", &selections)
+            .unwrap();
+        assert_eq!(command, "ssh localhost");
+
+        tasks.mappings[0].default = None;
+        tasks.mappings[0].passthrough = true;
+        let command = tasks
+            .apply_mappings("ssh ${mapping:host}", &selections)
+            .unwrap();
+        assert_eq!(command, "ssh qa");
+
+        let selections = IndexMap::from([("profile".to_string(), "prod".to_string())]);
+        let command = tasks
+            .apply_mappings("ssh ${mapping:host}", &selections)
+            .unwrap();
+        assert_eq!(command, "ssh prod.example.com");
+    }
+
+    #[test]
+    fn test_apply_mappings_composite() {
+        let mut tasks = Tasks::default();
+        tasks
+            .inputs
+            .push(create_test_input("env", vec!["dev", "prod"]));
+        tasks
+            .inputs
+            .push(create_test_input("region", vec!["eu", "us"]));
+        tasks.mappings.push(InputMapping {
+            id: "endpoint".to_string(),
+            input: None,
+            inputs: vec!["env".to_string(), "region".to_string()],
+            options: IndexMap::from([
+                ("prod/eu".to_string(), "eu.prod.example.com".into()),
+                ("prod/us".to_string(), "us.prod.example.com".into()),
+            ]),
+            default: None,
+            passthrough: false,
+        });
+
+        let required = tasks
+            .required_inputs_for_command("curl ${mapping:endpoint}")
+            .unwrap();
+        let ids: Vec<&str> = required.iter().map(|input| input.id.as_str()).collect();
+        assert_eq!(ids, vec!["env", "region"]);
+
+        let selections = IndexMap::from([
+            ("env".to_string(), "prod".to_string()),
+            ("region".to_string(), "us".to_string()),
+        ]);
+        let command = tasks
+            .apply_mappings("curl ${mapping:endpoint}", &selections)
+            .unwrap();
+        assert_eq!(command, "curl us.prod.example.com");
+
+        let selections = IndexMap::from([("env".to_string(), "prod".to_string())]);
+        assert!(tasks
+            .apply_mappings("curl ${mapping:endpoint}", &selections)
+            .is_err());
+    }
+
+    #[test]
+    fn test_apply_mappings_fields() {
+        let yaml = r#"
+version: "1.0.0"
+tasks: []
+inputs:
+  - id: build-type
+    options: [release, debug]
+mappings:
+  - id: target
+    input: build-type
+    options:
+      release:
+        dir: .build-release
+        jobs: 8
+      debug:
+        dir: .build-debug
+"#;
+        let tasks: Tasks = serde_yaml::from_str(yaml).unwrap();
+        let command = "cargo build -j ${mapping:target.jobs} --target-dir ${mapping:target.dir}";
+
+        let selections = IndexMap::from([("build-type".to_string(), "release".to_string())]);
+        assert_eq!(
+            tasks.apply_mappings(command, &selections).unwrap(),
+            "cargo build -j 8 --target-dir .build-release"
+        );
+
+        let selections = IndexMap::from([("build-type".to_string(), "debug".to_string())]);
+        assert!(tasks.apply_mappings(command, &selections).is_err());
+        assert!(tasks
+            .apply_mappings("ls ${mapping:target}", &selections)
+            .is_err());
+        assert!(tasks
+            .apply_mappings("ls ${mapping:other.dir}", &selections)
+            .is_err());
+    }
+
+    fn create_cascading_tasks() -> Tasks {
+        let mut tasks = Tasks::default();
+        let mut region = create_test_input("region", vec![]);
+        region.options_by = Some(OptionsBy {
+            input: "env".to_string(),
+            values: IndexMap::from([
+                ("dev".into(), vec!["local".into()]),
+                ("prod".into(), vec!["us".into(), "eu".into()]),
+            ]),
+        });
+        tasks.inputs.push(region);
+        tasks
+            .inputs
+            .push(create_test_input("env", vec!["dev", "prod"]));
+        tasks
+    }
+
+    #[test]
+    fn test_required_inputs_options_by() {
+        let mut tasks = create_cascading_tasks();
+
+        // env is prompted first, even if the command doesn't use it
+        let ids: Vec<&str> = tasks
+            .required_inputs_for_command("deploy ${input:region}")
+            .unwrap()
+            .iter()
+            .map(|input| input.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["env", "region"]);
+
+        tasks.inputs[1].options_by = Some(OptionsBy {
+            input: "region".to_string(),
+            values: IndexMap::new(),
+        });
+        let err = tasks
+            .required_inputs_for_command("deploy ${input:region}")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "inputs depend on each other: region -> env -> region"
+        );
+    }
+
+    #[test]
+    fn test_validate_config_options_by() {
+        use crate::validator::Validator;
+
+        let mut tasks = create_cascading_tasks();
+        let task = create_test_task("deploy", "deploy ${input:region}");
+        let validator = Validator {
+            verbose: false,
+            run_commands: false,
+        };
+        assert!(!validator
+            .validate_task_command(&task, &tasks)
+            .has_failures());
+
+        tasks.inputs[0].options_by.as_mut().unwrap().input = "missing".to_string();
+        assert!(validator
+            .validate_task_command(&task, &tasks)
+            .has_failures());
+
+        tasks.inputs[0].options_by.as_mut().unwrap().input = "region".to_string();
+        assert!(validator
+            .validate_task_command(&task, &tasks)
+            .has_failures());
+    }
+
+    #[test]
+    fn test_preset_selections_options_by() {
+        let tasks = create_cascading_tasks();
+        let command = "deploy ${input:env} ${input:region}";
+        let no_env = |_: &str| None;
+
+        let values = vec![
+            ("env".to_string(), "prod".to_string()),
+            ("region".to_string(), "eu".to_string()),
+        ];
+        assert!(tasks
+            .preset_selections_with(command, &values, no_env)
+            .is_ok());
+
+        let values = vec![
+            ("env".to_string(), "dev".to_string()),
+            ("region".to_string(), "eu".to_string()),
+        ];
+        assert!(tasks
+            .preset_selections_with(command, &values, no_env)
+            .is_err());
+
+        // without env all regions are valid until env is selected
+        let values = vec![("region".to_string(), "eu".to_string())];
+        let mut selections = tasks
+            .preset_selections_with(command, &values, no_env)
+            .unwrap();
+
+        selections.insert("env".to_string(), "dev".to_string());
+        let region = tasks.get_input("region").unwrap();
+        assert!(tasks.check_selection(region, "eu", &selections).is_err());
+
+        selections.insert("env".to_string(), "prod".to_string());
+        assert!(tasks.check_selection(region, "eu", &selections).is_ok());
+    }
+
+    #[test]
+    fn test_redact_selections() {
+        let mut tasks = Tasks::default();
+        tasks.inputs.push(create_test_input("user", vec![]));
+        let mut token = create_test_input("token", vec![]);
+        token.secret = true;
+        tasks.inputs.push(token);
+
+        let selections = IndexMap::from([
+            ("user".to_string(), "me".to_string()),
+            ("token".to_string(), "abc".to_string()),
+        ]);
+        let redacted = tasks.redact_selections(&selections);
+        assert_eq!(redacted["user"], "me");
+        assert_eq!(redacted["token"], "***");
+        assert!(tasks.is_secret_input("token"));
+        assert!(!tasks.is_secret_input("user"));
+    }
+
+    #[test]
+    fn test_preset_selections() {
+        let mut tasks = Tasks::default();
+        tasks
+            .inputs
+            .push(create_test_input("env", vec!["dev", "prod"]));
+        tasks
+            .inputs
+            .push(create_test_input("region", vec!["us", "eu"]));
+        tasks.inputs.push(create_test_input("unused", vec!["x"]));
+
+        let command = "deploy ${input:env} ${input:region}";
+        let env = |var: &str| match var {
+            "ALIASX_INPUT_ENV" => Some("dev".to_string()),
+            "ALIASX_INPUT_UNUSED" => Some("x".to_string()),
+            _ => None,
+        };
+
+        let selections = tasks.preset_selections_with(command, &[], env).unwrap();
+        assert_eq!(
+            selections,
+            IndexMap::from([("env".to_string(), "dev".to_string())])
+        );
+
+        // command line values win over the environment
+        let values = vec![
+            ("env".to_string(), "prod".to_string()),
+            ("region".to_string(), "eu".to_string()),
+        ];
+        let selections = tasks.preset_selections_with(command, &values, env).unwrap();
+        assert_eq!(selections["env"], "prod");
+        assert_eq!(selections["region"], "eu");
+
+        let values = vec![("env".to_string(), "qa".to_string())];
+        assert!(tasks.preset_selections_with(command, &values, env).is_err());
+
+        let values = vec![("unused".to_string(), "x".to_string())];
+        let err = tasks
+            .preset_selections_with(command, &values, env)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "the task doesn't use input 'unused'");
+    }
+
+    #[test]
+    fn test_check_selection() {
+        let mut tasks = Tasks::default();
+        tasks
+            .inputs
+            .push(create_test_input("env", vec!["dev", "prod"]));
+        let input = tasks.get_input("env").unwrap().clone();
+
+        assert!(tasks
+            .check_selection(&input, "dev", &IndexMap::new())
+            .is_ok());
+        let err = tasks
+            .check_selection(&input, "qa", &IndexMap::new())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value 'qa' for input 'env' (options: dev, prod)"
+        );
+    }
+
     #[test]
     fn test_get_input_exists() {
         let mut tasks = Tasks::default();
@@ -1,17 +1,18 @@
 use crate::{
     history::History,
-    input::Input,
-    input_mapping::InputMapping,
+    input::{Input, InputOption},
+    input_mapping::{InputMapping, KEY_SEPARATOR},
     task_collection::IndexedTask,
     tasks::{TaskEntry, Tasks},
 };
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 use owo_colors::OwoColorize;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ValidationStatus {
     Pass { message: String },
     Fail { message: String },
+    Warn { message: String },
     Skip { message: String },
 }
 
@@ -28,6 +29,12 @@ impl ValidationStatus {
         }
     }
 
+    fn warn(message: impl Into<String>) -> Self {
+        Self::Warn {
+            message: message.into(),
+        }
+    }
+
     fn skip(message: impl Into<String>) -> Self {
         Self::Skip {
             message: message.into(),
@@ -42,6 +49,10 @@ impl ValidationStatus {
         matches!(self, Self::Fail { .. })
     }
 
+    pub fn is_warn(&self) -> bool {
+        matches!(self, Self::Warn { .. })
+    }
+
     pub fn is_skip(&self) -> bool {
         matches!(self, Self::Skip { .. })
     }
@@ -50,6 +61,7 @@ impl ValidationStatus {
         match self {
             Self::Pass { message } => format!("{} {}", "✓".green().bold(), message.dimmed()),
             Self::Fail { message } => format!("{} {}", "✗".red().bold(), message),
+            Self::Warn { message } => format!("{} {}", "⚠".yellow().bold(), message.yellow()),
             Self::Skip { message } => {
                 format!("{} {} (skipped)", "⏭".yellow().bold(), message.dimmed())
             }
@@ -90,6 +102,10 @@ impl ValidationReport {
         self.statuses.iter().filter(|s| s.is_pass())
     }
 
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationStatus> {
+        self.statuses.iter().filter(|s| s.is_warn())
+    }
+
     pub fn skips(&self) -> impl Iterator<Item = &ValidationStatus> {
         self.statuses.iter().filter(|s| s.is_skip())
     }
@@ -102,6 +118,10 @@ impl ValidationReport {
         self.passes().count()
     }
 
+    pub fn warning_count(&self) -> usize {
+        self.warnings().count()
+    }
+
     pub fn skip_count(&self) -> usize {
         self.skips().count()
     }
@@ -138,8 +158,20 @@ impl ValidationReport {
     fn print_compact(&self) {
         let fail_count = self.failure_count();
 
-        if fail_count == 0 {
+        let warn_count = self.warning_count();
+
+        if fail_count == 0 && warn_count == 0 {
             println!("{} {}", "✓".green().bold(), self.validation_id.dimmed());
+        } else if fail_count == 0 {
+            println!(
+                "{} {} {}",
+                "⚠".yellow().bold(),
+                self.validation_id,
+                format!("({} warnings)", warn_count).yellow()
+            );
+            for status in self.warnings() {
+                println!("    {}", status.format());
+            }
         } else {
             println!(
                 "{} {} {}",
@@ -147,7 +179,7 @@ impl ValidationReport {
                 self.validation_id,
                 format!("({} issues)", fail_count).red()
             );
-            for status in self.failures() {
+            for status in self.failures().chain(self.warnings()) {
                 println!("    {}", status.format());
             }
         }
@@ -156,6 +188,9 @@ impl ValidationReport {
 
 pub struct Validator {
     pub verbose: bool,
+
+    /// dry-run the `options_command` of inputs
+    pub run_commands: bool,
 }
 
 impl Validator {
@@ -206,6 +241,7 @@ impl Validator {
         let mut report = ValidationReport::new(&entry.label);
 
         report.add_statuses(self.check_inputs(entry, source));
+        report.add_statuses(self.check_env_vars(entry, source));
         report.add_statuses(self.check_mappings(entry, source));
         report.add_statuses(self.check_conditions(entry));
 
@@ -215,48 +251,194 @@ impl Validator {
     fn check_inputs(&self, entry: &TaskEntry, source: &Tasks) -> Vec<ValidationStatus> {
         Input::extract_variables(&entry.command)
             .into_iter()
-            .filter_map(|input_id| self.check_input_defined(&input_id, source))
+            .flat_map(|input_id| self.check_input(&input_id, source))
             .collect()
     }
 
-    fn check_input_defined(&self, input_id: &str, source: &Tasks) -> Option<ValidationStatus> {
-        match source.get_input(input_id) {
-            Ok(_) if self.verbose => Some(ValidationStatus::pass(format!(
+    /// Inputs of one task must not share an `ALIASX_INPUT_<ID>` variable, eg. `build-type`
+    /// and `build_type`. Undefined inputs are reported by the other checks.
+    fn check_env_vars(&self, entry: &TaskEntry, source: &Tasks) -> Vec<ValidationStatus> {
+        let Ok(inputs) = source.required_inputs_for_command(&entry.command) else {
+            return Vec::new();
+        };
+
+        let mut seen: IndexMap<String, &str> = IndexMap::new(); // env var -> input id
+        let mut statuses = Vec::new();
+
+        for input in inputs {
+            if let Some(other) = seen.insert(input.env_var(), &input.id) {
+                statuses.push(ValidationStatus::fail(format!(
+                    "Inputs '{}' and '{}' are both preset by {}",
+                    other,
+                    input.id,
+                    input.env_var()
+                )));
+            }
+        }
+
+        statuses
+    }
+
+    fn check_input(&self, input_id: &str, source: &Tasks) -> Vec<ValidationStatus> {
+        let Ok(input) = source.get_input(input_id) else {
+            return vec![ValidationStatus::fail(format!(
+                "Input '{}' not defined",
+                input_id
+            ))];
+        };
+
+        let mut statuses = Vec::new();
+
+        if self.verbose {
+            statuses.push(ValidationStatus::pass(format!(
                 "Input '{}' defined",
                 input_id
-            ))),
-            Ok(_) => None,
-            Err(_) => Some(ValidationStatus::fail(format!(
-                "Input '{}' not defined",
+            )));
+        }
+
+        if input.separator.as_deref() == Some("") {
+            statuses.push(ValidationStatus::fail(format!(
+                "Input '{}' separator must not be empty",
                 input_id
+            )));
+        } else if input.multiple
+            && input
+                .options
+                .iter()
+                .any(|o| o.value.contains(input.separator()))
+        {
+            statuses.push(ValidationStatus::fail(format!(
+                "Input '{}' has options containing the separator '{}'",
+                input_id,
+                input.separator()
+            )));
+        }
+
+        if let Some(var) = input
+            .secret_env
+            .as_deref()
+            .filter(|var| !is_env_var_name(var))
+        {
+            statuses.push(ValidationStatus::fail(format!(
+                "Input '{}' secret_env '{}' is not a valid environment variable name",
+                input_id, var
+            )));
+        }
+
+        if input.options_by.is_some() {
+            if let Err(err) = source.ordered_inputs([input_id.to_string()]) {
+                statuses.push(ValidationStatus::fail(format!(
+                    "Input '{}' options_by: {:#}",
+                    input_id, err
+                )));
+            }
+        }
+
+        if let Err(err) = input.validate_rules() {
+            statuses.push(ValidationStatus::fail(format!(
+                "Input '{}': {:#}",
+                input_id, err
+            )));
+        }
+
+        if input.options_command.is_some() || input.options_glob.is_some() {
+            statuses.extend(self.check_dynamic_options(input, source));
+        }
+
+        if input.default_command.is_some() {
+            statuses.extend(self.check_default_command(input));
+        }
+
+        statuses
+    }
+
+    fn check_dynamic_options(&self, input: &Input, source: &Tasks) -> Option<ValidationStatus> {
+        if input.options_command.is_some() && !self.run_commands {
+            return self.verbose.then(|| {
+                ValidationStatus::skip(format!(
+                    "Input '{}' options_command not run (use --run-commands)",
+                    input.id
+                ))
+            });
+        }
+
+        match input.resolve_options(&source.config_dir()) {
+            Ok(resolved) => Some(ValidationStatus::pass(format!(
+                "Input '{}' resolved {} options",
+                input.id,
+                resolved.options.len()
+            ))),
+            Err(err) => Some(ValidationStatus::fail(format!(
+                "Input '{}': {:#}",
+                input.id, err
+            ))),
+        }
+    }
+
+    fn check_default_command(&self, input: &Input) -> Option<ValidationStatus> {
+        if !self.run_commands {
+            return self.verbose.then(|| {
+                ValidationStatus::skip(format!(
+                    "Input '{}' default_command not run (use --run-commands)",
+                    input.id
+                ))
+            });
+        }
+
+        // the command is checked even if default_env is set
+        let input = Input {
+            default_env: None,
+            ..input.clone()
+        };
+
+        match input.resolve_default() {
+            Ok((resolved, _)) => Some(ValidationStatus::pass(format!(
+                "Input '{}' default_command resolved '{}'",
+                input.id,
+                input.shown(resolved.default.as_deref().unwrap_or_default())
+            ))),
+            Err(err) => Some(ValidationStatus::fail(format!(
+                "Input '{}': {:#}",
+                input.id, err
             ))),
         }
     }
 
     fn check_mappings(&self, entry: &TaskEntry, source: &Tasks) -> Vec<ValidationStatus> {
+        let mut checked = IndexSet::new();
+
         InputMapping::extract_from_str(&entry.command)
             .into_iter()
-            .flat_map(|mapping_id| self.check_mapping(&mapping_id, source))
+            .flat_map(|reference| self.check_mapping(&reference, source, &mut checked))
             .collect()
     }
 
-    fn check_mapping(&self, mapping_id: &str, source: &Tasks) -> Vec<ValidationStatus> {
+    fn check_mapping(
+        &self,
+        reference: &str,
+        source: &Tasks,
+        checked: &mut IndexSet<String>,
+    ) -> Vec<ValidationStatus> {
         let mut statuses = Vec::new();
 
-        match source.get_mapping(mapping_id) {
-            Ok(mapping) => {
+        match source.resolve_mapping(reference) {
+            Ok((mapping, field)) => {
                 if self.verbose {
                     statuses.push(ValidationStatus::pass(format!(
                         "Mapping '{}' defined",
-                        mapping_id
+                        reference
                     )));
                 }
-                statuses.extend(self.check_mapping_inputs(mapping, source));
+                statuses.extend(self.check_mapping_field(mapping, field));
+                // fields of the same mapping share its inputs
+                if checked.insert(mapping.id.clone()) {
+                    statuses.extend(self.check_mapping_inputs(mapping, source));
+                }
             }
             Err(_) => {
                 statuses.push(ValidationStatus::fail(format!(
                     "Mapping '{}' not defined",
-                    mapping_id
+                    reference
                 )));
             }
         }
@@ -264,31 +446,208 @@ impl Validator {
         statuses
     }
 
-    // Ensure every option defined on the input has a corresponding entry in the mapping's options map.
+    // Ensure the referenced field (or plain value) exists for every option of the mapping.
+    fn check_mapping_field(
+        &self,
+        mapping: &InputMapping,
+        field: Option<&str>,
+    ) -> Vec<ValidationStatus> {
+        mapping
+            .options
+            .iter()
+            .map(|(option, value)| (option.as_str(), value))
+            .chain(mapping.default.iter().map(|value| ("default", value)))
+            .filter(|(_, value)| value.get(field).is_none())
+            .map(|(option, _)| match field {
+                Some(field) => ValidationStatus::fail(format!(
+                    "Mapping '{}' option '{}' doesn't define field '{}'",
+                    mapping.id, option, field
+                )),
+                None => ValidationStatus::fail(format!(
+                    "Mapping '{}' option '{}' has fields, reference one as ${{mapping:{}.<field>}}",
+                    mapping.id, option, mapping.id
+                )),
+            })
+            .collect()
+    }
+
+    // Ensure every option defined on the input (or combination of options for composite mappings)
+    // has a corresponding entry in the mapping's options map.
+    // Options missing from a mapping with a `default` or `passthrough` fallback are only warnings.
     fn check_mapping_inputs(
         &self,
         mapping: &InputMapping,
         source: &Tasks,
     ) -> Vec<ValidationStatus> {
-        match source.get_input(&mapping.input) {
-            Ok(input) => input
-                .options
-                .iter()
-                .filter(|option| !mapping.options.contains_key(*option))
+        let mut statuses = Vec::new();
+        if mapping.default.is_some() && mapping.passthrough {
+            statuses.push(ValidationStatus::warn(format!(
+                "Mapping '{}' sets both default and passthrough, passthrough is ignored",
+                mapping.id
+            )));
+        }
+
+        if mapping.passthrough {
+            for id in mapping
+                .input_ids()
+                .into_iter()
+                .filter(|id| source.is_secret_input(id))
+            {
+                statuses.push(ValidationStatus::warn(format!(
+                    "Mapping '{}' passes secret input '{}' through, the secret ends up in the command line",
+                    mapping.id, id
+                )));
+            }
+        }
+
+        statuses.extend(self.check_mapping_options(mapping, source));
+        statuses
+    }
+
+    fn check_mapping_options(
+        &self,
+        mapping: &InputMapping,
+        source: &Tasks,
+    ) -> Vec<ValidationStatus> {
+        if mapping.input.is_some() && !mapping.inputs.is_empty() {
+            return vec![ValidationStatus::fail(format!(
+                "Mapping '{}' sets both input and inputs",
+                mapping.id
+            ))];
+        }
+
+        if mapping.input.is_none() && mapping.inputs.is_empty() {
+            return vec![ValidationStatus::fail(format!(
+                "Mapping '{}' has no input",
+                mapping.id
+            ))];
+        }
+
+        let mut statuses = Vec::new();
+        let mut inputs: Vec<(&Input, Input)> = Vec::new(); // input and the options it can take
+        let mut unchecked = false;
+        for id in mapping.input_ids() {
+            match source.get_input(id) {
+                // free text can't be listed, the fallback covers it
+                Ok(input) if input.is_free_text() && mapping.has_fallback() => unchecked = true,
+                Ok(input) if input.is_free_text() => {
+                    return vec![ValidationStatus::fail(format!(
+                        "Mapping '{}' references free-text input '{}'",
+                        mapping.id, id
+                    ))];
+                }
+                Ok(input) if input.multiple && mapping.is_composite() => {
+                    return vec![ValidationStatus::fail(format!(
+                        "Mapping '{}' combines multiple-selection input '{}'",
+                        mapping.id, id
+                    ))];
+                }
+                Ok(input) => match self.mapped_options(mapping, input, source) {
+                    Ok(resolved) => {
+                        // `<value>/<value>` keys can't be told apart
+                        if mapping.is_composite()
+                            && resolved
+                                .option_values()
+                                .iter()
+                                .any(|o| o.contains(KEY_SEPARATOR))
+                        {
+                            statuses.push(ValidationStatus::fail(format!(
+                                "Mapping '{}' combines input '{}' with options containing '{}'",
+                                mapping.id, id, KEY_SEPARATOR
+                            )));
+                        }
+                        inputs.push((input, resolved))
+                    }
+                    Err(status) => {
+                        statuses.push(status);
+                        unchecked = true;
+                    }
+                },
+                Err(_) => {
+                    return vec![ValidationStatus::fail(format!(
+                        "Mapping '{}' references undefined input '{}'",
+                        mapping.id, id
+                    ))];
+                }
+            }
+        }
+
+        if unchecked {
+            return statuses;
+        }
+
+        // every combination of the options of the mapping's inputs
+        let mut combinations: Vec<Vec<&str>> = vec![vec![]];
+        for (_, resolved) in &inputs {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combination| {
+                    resolved.option_values().into_iter().map(move |option| {
+                        let mut next = combination.clone();
+                        next.push(option);
+                        next
+                    })
+                })
+                .collect();
+        }
+
+        statuses.extend(
+            combinations
+                .into_iter()
+                .filter(|combination| {
+                    let selections: IndexMap<&str, &str> = inputs
+                        .iter()
+                        .map(|(input, _)| input.id.as_str())
+                        .zip(combination.iter().copied())
+                        .collect();
+                    inputs
+                        .iter()
+                        .zip(combination)
+                        .all(|((input, _), value)| can_select(input, value, &selections))
+                })
+                .map(|combination| InputMapping::key(&combination))
+                .filter(|option| !mapping.options.contains_key(option))
                 .map(|option| {
-                    ValidationStatus::fail(format!(
+                    let message = format!(
                         "Mapping '{}' doesn't define option for input '{}'",
                         mapping.id, option
-                    ))
-                })
-                .collect(),
-            Err(_) => {
-                vec![ValidationStatus::fail(format!(
-                    "Mapping '{}' references undefined input '{}'",
-                    mapping.id, mapping.input
-                ))]
-            }
+                    );
+                    if mapping.has_fallback() {
+                        ValidationStatus::warn(format!("{}, using fallback", message))
+                    } else {
+                        ValidationStatus::fail(message)
+                    }
+                }),
+        );
+        statuses
+    }
+
+    /// The options `input` can take for `mapping` - all of its `options_by` options and, as in
+    /// [`Self::check_dynamic_options`], the files matching `options_glob` and the output of
+    /// `options_command` with `--run-commands`. Otherwise the coverage can't be checked.
+    fn mapped_options(
+        &self,
+        mapping: &InputMapping,
+        input: &Input,
+        source: &Tasks,
+    ) -> Result<Input, ValidationStatus> {
+        let unchecked = |reason: String| {
+            ValidationStatus::warn(format!(
+                "Mapping '{}' coverage of input '{}' not checked: {}",
+                mapping.id, input.id, reason
+            ))
+        };
+
+        if input.options_command.is_some() && !self.run_commands {
+            return Err(unchecked(
+                "options_command not run (use --run-commands)".to_string(),
+            ));
         }
+
+        input
+            .select_options_by(&IndexMap::new())
+            .and_then(|all| all.resolve_options(&source.config_dir()))
+            .map_err(|err| unchecked(format!("{:#}", err)))
     }
 
     fn check_conditions(&self, entry: &TaskEntry) -> Option<ValidationStatus> {
@@ -312,14 +671,14 @@ impl Validator {
 
     pub fn print_single_report(&self, report: &ValidationReport) {
         report.print(self.verbose);
-        println!("");
+        println!();
     }
 
     pub fn print_report(&self, reports: &[ValidationReport]) {
         for report in reports.iter() {
             report.print(self.verbose);
         }
-        println!("");
+        println!();
     }
 
     pub fn print_header() {
@@ -336,6 +695,7 @@ impl Validator {
         let failed_tasks = reports.iter().filter(|r| r.has_failures()).count();
         let passed_tasks = total_tasks - failed_tasks;
         let total_failures = reports.iter().map(|r| r.failure_count()).sum::<usize>();
+        let total_warnings = reports.iter().map(|r| r.warning_count()).sum::<usize>();
 
         println!("{}", "═".repeat(60).dimmed());
         println!("{}", "  SUMMARY".bold().cyan());
@@ -365,6 +725,14 @@ impl Validator {
             );
         }
 
+        if total_warnings > 0 {
+            println!(
+                "  {} {} total",
+                "⚠".yellow().bold(),
+                format!("{} warnings", total_warnings).yellow()
+            );
+        }
+
         println!("{}", "═".repeat(60).dimmed());
 
         if failed_tasks == 0 {
@@ -382,3 +750,25 @@ impl Validator {
         }
     }
 }
+
+fn is_env_var_name(name: &str) -> bool {
+    !name.starts_with(|c: char| c.is_ascii_digit())
+        && !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// False when `value` is an `options_by` option of `input` that isn't offered for the value
+/// selected for the input it depends on.
+fn can_select(input: &Input, value: &str, selections: &IndexMap<&str, &str>) -> bool {
+    let Some(by) = &input.options_by else {
+        return true;
+    };
+    let Some(selected) = selections.get(by.input.as_str()) else {
+        return true;
+    };
+
+    let has = |options: &[InputOption]| options.iter().any(|o| o.value == value);
+    has(&input.options)
+        || !by.values.values().any(|options| has(options))
+        || by.values.get(*selected).is_some_and(|options| has(options))
+}
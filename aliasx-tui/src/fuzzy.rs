@@ -1,5 +1,8 @@
-use aliasx_core::history::{History, HistoryEntry};
-use crossterm::event::{self, Event, KeyCode};
+use aliasx_core::{
+    history::{History, HistoryEntry},
+    input::InputOption,
+};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
@@ -8,6 +11,7 @@ use ratatui::{
 };
 
 use crate::{
+    text_input::ValueCheck,
     widgets::{footer, highlight_match},
     TuiSession,
 };
@@ -22,6 +26,21 @@ pub struct FuzzyConfig {
 
     pub initial_query: String,
     pub initial_position: usize,
+
+    /// Extra actions triggered with Ctrl+key on the selected item.
+    pub actions: Vec<FuzzyAction>,
+
+    /// Shown above the list, eg. why items are missing.
+    pub message: Option<String>,
+
+    /// Items toggled initially when selecting multiple items.
+    pub initial_toggled: Vec<usize>,
+
+    /// Checks typed values, see [`fuzzy_input`].
+    pub check: Option<ValueCheck>,
+
+    /// Show `•` instead of the typed text and the added values, eg. for passwords.
+    pub mask: bool,
 }
 
 impl Default for FuzzyConfig {
@@ -32,10 +51,52 @@ impl Default for FuzzyConfig {
             filters: vec![],
             initial_query: String::new(),
             initial_position: 0,
+            actions: vec![],
+            message: None,
+            initial_toggled: vec![],
+            check: None,
+            mask: false,
         }
     }
 }
 
+/// An action bound to Ctrl+`key`, shown in the footer as `hint`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FuzzyAction {
+    pub key: char,
+    pub hint: &'static str,
+    pub name: &'static str,
+}
+
+/// The selected item and the action it was selected with (`None` for enter).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FuzzySelection {
+    pub idx: usize,
+    pub action: Option<FuzzyAction>,
+}
+
+/// Result of [`fuzzy_input`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum FuzzyInput {
+    /// Index of the picked item.
+    Item(usize),
+
+    /// Text typed in the search bar.
+    Custom(String),
+}
+
+enum Picked {
+    Selection(FuzzySelection),
+    Query(String),
+    Multiple(Vec<FuzzyInput>),
+}
+
+#[derive(Clone, Copy)]
+struct Mode {
+    allow_custom: bool,
+    multiple: bool,
+}
+
 pub trait FuzzyList {
     fn label(&self) -> &str;
 
@@ -66,6 +127,29 @@ impl FuzzyList for String {
     }
 }
 
+impl FuzzyList for InputOption {
+    fn label(&self) -> &str {
+        InputOption::label(self)
+    }
+
+    fn detail(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    fn searchable(&self) -> String {
+        [
+            Some(self.label()),
+            Some(self.value.as_str()),
+            self.description.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+    }
+}
+
 impl FuzzyList for HistoryEntry {
     fn label(&self) -> &str {
         &self.task_name
@@ -105,24 +189,33 @@ fn filter_tabs(filters: &[String], active: usize) -> Line<'_> {
     Line::from(spans)
 }
 
-fn build_list_item<'a, T: FuzzyList>(item: &'a T, query: &str) -> ListItem<'a> {
-     let highlighted = highlight_match(item.label(), query);
-     let prefix = item.label_prefix();
-     let suffix = item.label_suffix();
-
-     if prefix.is_none() && suffix.is_none() {
-         return ListItem::new(highlighted);
-     }
-
-     let mut spans = vec![];
-     if let Some(prefix) = prefix {
-         spans.push(Span::styled(prefix, Style::default().fg(Color::DarkGray)));
-     }
-     spans.extend(highlighted.spans);
-     if let Some(suffix) = suffix {
-         spans.push(Span::styled(suffix, Style::default().fg(Color::DarkGray)));
-     }
-     ListItem::new(Line::from(spans))
+fn build_list_item<'a, T: FuzzyList>(
+    item: &'a T,
+    query: &str,
+    toggled: Option<bool>,
+) -> ListItem<'a> {
+    let highlighted = highlight_match(item.label(), query);
+    let prefix = item.label_prefix();
+    let suffix = item.label_suffix();
+
+    if prefix.is_none() && suffix.is_none() && toggled.is_none() {
+        return ListItem::new(highlighted);
+    }
+
+    let mut spans = vec![];
+    match toggled {
+        Some(true) => spans.push(Span::styled("◉ ", Style::default().fg(Color::Green))),
+        Some(false) => spans.push(Span::styled("○ ", Style::default().fg(Color::DarkGray))),
+        None => {}
+    }
+    if let Some(prefix) = prefix {
+        spans.push(Span::styled(prefix, Style::default().fg(Color::DarkGray)));
+    }
+    spans.extend(highlighted.spans);
+    if let Some(suffix) = suffix {
+        spans.push(Span::styled(suffix, Style::default().fg(Color::DarkGray)));
+    }
+    ListItem::new(Line::from(spans))
 }
 
 pub fn fuzzy_finder<T: FuzzyList>(
@@ -131,6 +224,80 @@ pub fn fuzzy_finder<T: FuzzyList>(
     config: FuzzyConfig,
     session: &mut TuiSession,
 ) -> anyhow::Result<usize> {
+    Ok(fuzzy_select(items, prompt, config, session)?.idx)
+}
+
+/// Same as [`fuzzy_finder`] but also reports which of `config.actions` was used.
+pub fn fuzzy_select<T: FuzzyList>(
+    items: &[T],
+    prompt: &str,
+    config: FuzzyConfig,
+    session: &mut TuiSession,
+) -> anyhow::Result<FuzzySelection> {
+    let mode = Mode {
+        allow_custom: false,
+        multiple: false,
+    };
+
+    match run_fuzzy(items, prompt, config, mode, session)? {
+        Picked::Selection(selection) => Ok(selection),
+        Picked::Query(_) | Picked::Multiple(_) => unreachable!("single selection only"),
+    }
+}
+
+/// Same as [`fuzzy_finder`] but the typed text can be used instead of an item:
+/// with enter when nothing matches or with Ctrl+t.
+pub fn fuzzy_input<T: FuzzyList>(
+    items: &[T],
+    prompt: &str,
+    config: FuzzyConfig,
+    session: &mut TuiSession,
+) -> anyhow::Result<FuzzyInput> {
+    let mode = Mode {
+        allow_custom: true,
+        multiple: false,
+    };
+
+    Ok(match run_fuzzy(items, prompt, config, mode, session)? {
+        Picked::Selection(selection) => FuzzyInput::Item(selection.idx),
+        Picked::Query(query) => FuzzyInput::Custom(query),
+        Picked::Multiple(_) => unreachable!("single selection only"),
+    })
+}
+
+/// Selects several items - Tab toggles the highlighted item and enter without any toggled
+/// items selects the highlighted one. With `allow_custom` Ctrl+t adds the typed text.
+/// Items are returned in list order followed by the typed values.
+pub fn fuzzy_multi_input<T: FuzzyList>(
+    items: &[T],
+    prompt: &str,
+    config: FuzzyConfig,
+    allow_custom: bool,
+    session: &mut TuiSession,
+) -> anyhow::Result<Vec<FuzzyInput>> {
+    let mode = Mode {
+        allow_custom,
+        multiple: true,
+    };
+
+    Ok(match run_fuzzy(items, prompt, config, mode, session)? {
+        Picked::Selection(selection) => vec![FuzzyInput::Item(selection.idx)],
+        Picked::Query(query) => vec![FuzzyInput::Custom(query)],
+        Picked::Multiple(selections) => selections,
+    })
+}
+
+fn run_fuzzy<T: FuzzyList>(
+    items: &[T],
+    prompt: &str,
+    config: FuzzyConfig,
+    mode: Mode,
+    session: &mut TuiSession,
+) -> anyhow::Result<Picked> {
+    let Mode {
+        allow_custom,
+        multiple,
+    } = mode;
     let mut selected = config.initial_position;
     let mut query = config.initial_query;
     let mut list_state = ListState::default();
@@ -138,6 +305,20 @@ pub fn fuzzy_finder<T: FuzzyList>(
     let mut filter_idx = 0usize;
     let has_filters = !config.filters.is_empty();
     let num_filters = config.filters.len();
+    let mut toggled: Vec<usize> = config.initial_toggled.clone();
+    let mut custom_values: Vec<String> = vec![];
+    let mut error: Option<String> = None;
+    let check = |value: &str| match config.check.as_ref().map(|check| check(value)) {
+        Some(Err(err)) => Err(format!("{:#}", err)),
+        _ => Ok(()),
+    };
+    let shown = |value: &str| {
+        if config.mask {
+            "•".repeat(value.chars().count())
+        } else {
+            value.to_string()
+        }
+    };
 
     loop {
         let q = query.to_lowercase();
@@ -147,7 +328,7 @@ pub fn fuzzy_finder<T: FuzzyList>(
             .iter()
             .enumerate()
             .filter(|(_, item)| {
-                let matches_filter = active_filter.map_or(true, |f| item.match_filter(f));
+                let matches_filter = active_filter.is_none_or(|f| item.match_filter(f));
                 let matches_query = item.searchable().contains(&q);
                 matches_filter && matches_query
             })
@@ -169,11 +350,15 @@ pub fn fuzzy_finder<T: FuzzyList>(
             // search bar
             let display_query = if query.is_empty() {
                 Span::styled(
-                    "type to search...",
+                    if allow_custom {
+                        "type to search or enter a value..."
+                    } else {
+                        "type to search..."
+                    },
                     Style::default().fg(Color::DarkGray).italic(),
                 )
             } else {
-                Span::raw(&query)
+                Span::raw(shown(&query))
             };
             f.render_widget(
                 Paragraph::new(Line::from(vec![
@@ -197,12 +382,28 @@ pub fn fuzzy_finder<T: FuzzyList>(
 
             let list_title = if has_filters {
                 filter_tabs(&config.filters, filter_idx)
+            } else if let Some(message) = error.as_ref().or(config.message.as_ref()) {
+                Line::styled(format!(" {} ", message), Style::default().fg(Color::Red))
+            } else if !custom_values.is_empty() {
+                Line::styled(
+                    format!(
+                        " added: {} ",
+                        custom_values
+                            .iter()
+                            .map(|value| shown(value))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    Style::default().fg(Color::Green),
+                )
             } else {
                 Line::raw("")
             };
             let list_items: Vec<ListItem> = filtered
                 .iter()
-                .map(|(_, item)| build_list_item(*item, &query))
+                .map(|(i, item)| {
+                    build_list_item(*item, &query, multiple.then(|| toggled.contains(i)))
+                })
                 .collect();
             f.render_stateful_widget(
                 List::new(list_items)
@@ -252,11 +453,63 @@ pub fn fuzzy_finder<T: FuzzyList>(
                 hints.push(("esc", "cancel"));
             }
 
+            for action in config.actions.iter() {
+                hints.push((action.hint, action.name));
+            }
+
+            if multiple {
+                hints.push(("tab", "toggle"));
+            }
+
+            if allow_custom {
+                hints.push((
+                    "^t",
+                    if multiple {
+                        "add typed value"
+                    } else {
+                        "use typed value"
+                    },
+                ));
+            }
+
             f.render_widget(footer(&hints), v[2]);
         })?;
 
         if let Event::Key(key) = event::read()? {
             match key.code {
+                KeyCode::Char('t')
+                    if allow_custom && key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    if let Err(err) = check(&query) {
+                        error = Some(err);
+                    } else if !multiple {
+                        return Ok(Picked::Query(query));
+                    } else if !query.is_empty() {
+                        custom_values.push(std::mem::take(&mut query));
+                        selected = 0;
+                        error = None;
+                    }
+                }
+                KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    let action = config.actions.iter().find(|a| a.key == c);
+                    if let (Some(action), Some((orig_idx, _))) = (action, filtered.get(selected)) {
+                        return Ok(Picked::Selection(FuzzySelection {
+                            idx: *orig_idx,
+                            action: Some(*action),
+                        }));
+                    }
+                }
+                KeyCode::Tab if multiple => {
+                    if let Some((orig_idx, _)) = filtered.get(selected) {
+                        match toggled.iter().position(|i| i == orig_idx) {
+                            Some(pos) => {
+                                toggled.remove(pos);
+                            }
+                            None => toggled.push(*orig_idx),
+                        }
+                        selected = (selected + 1) % filtered.len();
+                    }
+                }
                 KeyCode::Tab if has_filters => {
                     filter_idx = (filter_idx + 1) % (num_filters);
                     selected = 0;
@@ -269,10 +522,12 @@ pub fn fuzzy_finder<T: FuzzyList>(
                 KeyCode::Char(c) => {
                     query.push(c);
                     selected = 0;
+                    error = None;
                 }
                 KeyCode::Backspace => {
                     query.pop();
                     selected = 0;
+                    error = None;
                 }
                 KeyCode::Up => {
                     selected = if selected > 0 {
@@ -288,9 +543,27 @@ pub fn fuzzy_finder<T: FuzzyList>(
                         0
                     }
                 }
+                KeyCode::Enter if multiple && !(toggled.is_empty() && custom_values.is_empty()) => {
+                    toggled.sort();
+                    return Ok(Picked::Multiple(
+                        toggled
+                            .into_iter()
+                            .map(FuzzyInput::Item)
+                            .chain(custom_values.into_iter().map(FuzzyInput::Custom))
+                            .collect(),
+                    ));
+                }
                 KeyCode::Enter => {
                     if let Some((orig_idx, _)) = filtered.get(selected) {
-                        return Ok(*orig_idx);
+                        return Ok(Picked::Selection(FuzzySelection {
+                            idx: *orig_idx,
+                            action: None,
+                        }));
+                    } else if allow_custom {
+                        match check(&query) {
+                            Ok(()) => return Ok(Picked::Query(query)),
+                            Err(err) => error = Some(err),
+                        }
                     }
                 }
                 KeyCode::Esc => return Err(anyhow::anyhow!("No selection made")),
@@ -1,8 +1,13 @@
 mod fuzzy;
 mod session;
 mod task_fuzzy;
+mod text_input;
 mod widgets;
 
-pub use fuzzy::{fuzzy_finder, FuzzyConfig, FuzzyList};
+pub use fuzzy::{
+    fuzzy_finder, fuzzy_input, fuzzy_multi_input, fuzzy_select, FuzzyAction, FuzzyConfig,
+    FuzzyInput, FuzzyList, FuzzySelection,
+};
 pub use session::TuiSession;
 pub use task_fuzzy::task_fuzzy_finder;
+pub use text_input::{text_input, TextInputConfig, ValueCheck};
@@ -1,4 +1,7 @@
-use std::{io, ops::{Deref, DerefMut}};
+use std::{
+    io,
+    ops::{Deref, DerefMut},
+};
 
 use anyhow::Result;
 use crossterm::{
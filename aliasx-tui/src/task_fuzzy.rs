@@ -3,8 +3,8 @@ use aliasx_core::task_filter::TaskFilter;
 use anyhow::Result;
 
 use crate::{
-    fuzzy::{FuzzyConfig, FuzzyList},
-    fuzzy_finder, TuiSession,
+    fuzzy::{FuzzyAction, FuzzyConfig, FuzzyList, FuzzySelection},
+    fuzzy_select, TuiSession,
 };
 
 struct TaskFuzzyItem {
@@ -48,7 +48,8 @@ pub fn task_fuzzy_finder(
     session: &mut TuiSession,
     query: &str,
     verbose: bool,
-) -> Result<usize> {
+    actions: Vec<FuzzyAction>,
+) -> Result<FuzzySelection> {
     let width = collection.width_idx();
 
     let items: Vec<TaskFuzzyItem> = itasks
@@ -58,29 +59,38 @@ pub fn task_fuzzy_finder(
             id_prefix: format!("{:0>width$} ", t.idx),
             task_label: t.task.label.clone(),
             detail: t.task.command.clone(),
-            label_suffix: match &t.task.id {
-                Some(id) => Some(format!(" [{}]", id)),
-                None => Option::None,
-            },
+            label_suffix: t.task.id.as_ref().map(|id| format!(" [{}]", id)),
             scope_key: Some(t.source.scope.to_string()),
         })
         .collect();
 
-    let sel = fuzzy_finder(
+    // native aliases and git aliases are only loaded on request - hide their tabs otherwise
+    let mut filters = vec![
+        TaskFilter::All.to_string(),
+        TaskFilter::Local.to_string(),
+        TaskFilter::Global.to_string(),
+    ];
+    for scope in [TaskFilter::Native, TaskFilter::Git] {
+        if itasks.iter().any(|t| t.source.scope == scope) {
+            filters.push(scope.to_string());
+        }
+    }
+
+    let sel = fuzzy_select(
         &items,
         "Search",
         FuzzyConfig {
             show_details: verbose,
-            filters: vec![
-                TaskFilter::All.to_string(),
-                TaskFilter::Local.to_string(),
-                TaskFilter::Global.to_string(),
-            ],
+            filters,
             initial_query: query.to_string(),
+            actions,
             ..FuzzyConfig::default()
         },
         session,
     )?;
 
-    Ok(items[sel].original_idx)
+    Ok(FuzzySelection {
+        idx: items[sel.idx].original_idx,
+        action: sel.action,
+    })
 }
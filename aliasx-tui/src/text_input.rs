@@ -0,0 +1,107 @@
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{widgets::footer, TuiSession};
+
+/// Checks an entered value - the error is shown inline and the value isn't accepted.
+pub type ValueCheck = Box<dyn Fn(&str) -> anyhow::Result<()>>;
+
+#[derive(Default)]
+pub struct TextInputConfig {
+    /// Text shown below the input field, eg. the expected format.
+    pub hint: Option<String>,
+
+    pub initial_value: String,
+
+    pub check: Option<ValueCheck>,
+
+    /// Show `•` instead of the typed characters, eg. for passwords.
+    pub mask: bool,
+}
+
+/// Prompts for a line of free text. Returns the entered text on enter.
+pub fn text_input(
+    prompt: &str,
+    config: TextInputConfig,
+    session: &mut TuiSession,
+) -> anyhow::Result<String> {
+    let mut value = config.initial_value;
+    let mut error: Option<String> = None;
+
+    loop {
+        let shown = if config.mask {
+            "•".repeat(value.chars().count())
+        } else {
+            value.clone()
+        };
+
+        session.draw(|f| {
+            let v = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(3),
+                    Constraint::Min(1),
+                    Constraint::Length(1),
+                ])
+                .split(f.area());
+
+            f.render_widget(
+                Paragraph::new(Line::from(vec![
+                    Span::styled("> ", Style::default().fg(Color::Yellow)),
+                    Span::raw(&shown),
+                    Span::styled("█", Style::default().fg(Color::DarkGray)),
+                ]))
+                .block(Block::default().title(prompt).borders(Borders::ALL)),
+                v[0],
+            );
+
+            if let Some(error) = &error {
+                f.render_widget(
+                    Paragraph::new(Span::styled(
+                        error.as_str(),
+                        Style::default().fg(Color::Red),
+                    )),
+                    v[1],
+                );
+            } else if let Some(hint) = &config.hint {
+                f.render_widget(
+                    Paragraph::new(Span::styled(
+                        hint.as_str(),
+                        Style::default().fg(Color::DarkGray).italic(),
+                    )),
+                    v[1],
+                );
+            }
+
+            f.render_widget(footer(&[("^u", "clear")]), v[2]);
+        })?;
+
+        if let Event::Key(key) = event::read()? {
+            match key.code {
+                KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    value.clear();
+                    error = None;
+                }
+                KeyCode::Char(c) => {
+                    value.push(c);
+                    error = None;
+                }
+                KeyCode::Backspace => {
+                    value.pop();
+                    error = None;
+                }
+                KeyCode::Enter => match config.check.as_ref().map(|check| check(&value)) {
+                    Some(Err(err)) => error = Some(format!("{:#}", err)),
+                    _ => return Ok(value),
+                },
+                KeyCode::Esc => return Err(anyhow::anyhow!("No input made")),
+                _ => {}
+            }
+        }
+    }
+}